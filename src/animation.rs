@@ -5,6 +5,13 @@ use std::{num::NonZeroU8, time::Duration};
 pub struct Animation {
     pub frames: Vec<Image>,
     pub fps: NonZeroU8,
+
+    /// Duration of the frame at the same index in `frames`.
+    ///
+    /// Frames without an entry (or with `None`) last for [`Animation::get_frame_duration()`].
+    /// When reordering or removing `frames` directly, apply the same change here so that each
+    /// duration stays with its frame.
+    pub durations: Vec<Option<Duration>>,
 }
 
 impl Animation {
@@ -16,16 +23,28 @@ impl Animation {
         Self {
             frames: vec![frame],
             fps: NonZeroU8::MAX,
+            durations: vec![None],
         }
     }
 
     pub fn frame(mut self, frame: Image) -> Self {
+        self.sync_durations();
+        self.frames.push(frame);
+        self.durations.push(None);
+        self
+    }
+
+    pub fn frame_with_duration(mut self, frame: Image, duration: Duration) -> Self {
+        self.sync_durations();
         self.frames.push(frame);
+        self.durations.push(Some(duration));
         self
     }
 
     pub fn frames(mut self, frames: impl IntoIterator<Item = Image>) -> Self {
+        self.sync_durations();
         self.frames.extend(frames);
+        self.sync_durations();
         self
     }
 
@@ -74,8 +93,18 @@ impl Animation {
         Duration::from_secs(1) / u32::from(self.fps.get())
     }
 
+    pub fn get_nth_frame_duration(&self, n: usize) -> Duration {
+        // Durations past the end of `frames` are ignored.
+        self.durations
+            .get(n)
+            .filter(|_| n < self.frames.len())
+            .copied()
+            .flatten()
+            .unwrap_or_else(|| self.get_frame_duration())
+    }
+
     pub fn get_duration(&self) -> Duration {
-        Duration::from_secs(self.frames.len() as u64) / u32::from(self.fps.get())
+        self.get_nth_frame_time(self.frames.len())
     }
 
     pub fn get_max_frame_size(&self) -> Size {
@@ -87,7 +116,19 @@ impl Animation {
     }
 
     pub fn get_nth_frame_time(&self, n: usize) -> Duration {
-        Duration::from_secs(n as u64) / u32::from(self.fps.get())
+        (0..n).map(|i| self.get_nth_frame_duration(i)).sum()
+    }
+
+    /// Returns the frame being displayed at `time`, or `None` if `time` is past the end.
+    pub fn frame_at(&self, time: Duration) -> Option<&Image> {
+        let mut end = Duration::ZERO;
+        for (i, frame) in self.frames.iter().enumerate() {
+            end += self.get_nth_frame_duration(i);
+            if time < end {
+                return Some(frame);
+            }
+        }
+        None
     }
 
     pub fn map_frame<F>(self, f: F) -> Animation
@@ -97,6 +138,7 @@ impl Animation {
         Animation {
             frames: self.frames.into_iter().map(f).collect(),
             fps: self.fps,
+            durations: self.durations,
         }
     }
 
@@ -106,6 +148,12 @@ impl Animation {
             frame
         })
     }
+
+    // `frames` and `durations` are public and may have been modified directly, so the lengths are
+    // matched again before appending.
+    fn sync_durations(&mut self) {
+        self.durations.resize(self.frames.len(), None);
+    }
 }
 
 impl Default for Animation {
@@ -113,6 +161,59 @@ impl Default for Animation {
         Self {
             frames: Vec::new(),
             fps: NonZeroU8::new(1).expect("unreachable"),
+            durations: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_durations_work() {
+        let frames = [Image::new().name("a"), Image::new().name("b")];
+        let anime = Animation::new()
+            .fps10()
+            .frame(frames[0].clone())
+            .frame_with_duration(frames[1].clone(), Duration::from_secs(2))
+            .frame(frames[0].clone());
+
+        assert_eq!(anime.get_nth_frame_duration(0), Duration::from_millis(100));
+        assert_eq!(anime.get_nth_frame_duration(1), Duration::from_secs(2));
+        assert_eq!(anime.get_nth_frame_time(2), Duration::from_millis(2100));
+        assert_eq!(anime.get_duration(), Duration::from_millis(2200));
+
+        assert_eq!(anime.frame_at(Duration::from_millis(99)), Some(&frames[0]));
+        assert_eq!(anime.frame_at(Duration::from_millis(100)), Some(&frames[1]));
+        assert_eq!(
+            anime.frame_at(Duration::from_millis(2150)),
+            Some(&frames[0])
+        );
+        assert_eq!(anime.frame_at(Duration::from_millis(2200)), None);
+
+        let anime = anime
+            .frames(frames.clone())
+            .map_frame(|frame| frame.name("c"));
+        assert_eq!(anime.get_nth_frame_duration(3), Duration::from_millis(100));
+        assert_eq!(anime.get_duration(), Duration::from_millis(2400));
+
+        let mut anime = anime;
+        anime.frames.truncate(1);
+        assert_eq!(anime.get_nth_frame_duration(1), Duration::from_millis(100));
+        let anime = anime.frame(frames[1].clone());
+        assert_eq!(anime.get_nth_frame_duration(1), Duration::from_millis(100));
+
+        let mut anime = Animation {
+            frames: frames.to_vec(),
+            fps: NonZeroU8::new(10).unwrap(),
+            durations: vec![None, Some(Duration::from_secs(2))],
+        };
+        anime.frames.reverse();
+        anime.durations.reverse();
+        assert_eq!(anime.get_nth_frame_duration(0), Duration::from_secs(2));
+        assert_eq!(anime.get_nth_frame_duration(1), Duration::from_millis(100));
+        let anime = anime.frame(frames[0].clone());
+        assert_eq!(anime.get_duration(), Duration::from_millis(2200));
+    }
+}
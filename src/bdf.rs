@@ -0,0 +1,303 @@
+use crate::{
+    font::{BitmapFont, Glyph},
+    Color, Image, Pixel, Position,
+};
+use std::io::Read;
+
+#[derive(Debug)]
+pub enum BdfError {
+    Io(std::io::Error),
+    Malformed { line: usize, reason: String },
+}
+
+impl std::fmt::Display for BdfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read BDF font: {e}"),
+            Self::Malformed { line, reason } => {
+                write!(f, "malformed BDF font at line {line}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BdfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Malformed { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for BdfError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Font in the Glyph Bitmap Distribution Format (BDF).
+#[derive(Debug)]
+pub struct BdfFont {
+    font: BitmapFont,
+}
+
+impl BdfFont {
+    pub fn new(font: BitmapFont) -> Self {
+        Self { font }
+    }
+
+    /// Reads a BDF font, drawing the glyphs in black.
+    ///
+    /// Glyph images are placed so that their top line is at `y = 0`. Glyphs without an encoding
+    /// are skipped.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, BdfError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let font = Parser::new(&text).parse()?;
+        Ok(Self { font })
+    }
+
+    pub fn into_font(self) -> BitmapFont {
+        self.font
+    }
+}
+
+struct Parser<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().enumerate(),
+            line: 0,
+        }
+    }
+
+    fn parse(mut self) -> Result<BitmapFont, BdfError> {
+        let (keyword, _) = self.next_line()?;
+        if keyword != "STARTFONT" {
+            return Err(self.error("expected STARTFONT"));
+        }
+
+        let mut bounding_box = None;
+        let mut ascent = None;
+        let mut descent = None;
+        let mut glyphs = Vec::new();
+        loop {
+            let (keyword, args) = self.next_line()?;
+            match keyword {
+                "FONTBOUNDINGBOX" => bounding_box = Some(self.numbers::<4>(args)?),
+                "FONT_ASCENT" => ascent = Some(self.numbers::<1>(args)?[0]),
+                "FONT_DESCENT" => descent = Some(self.numbers::<1>(args)?[0]),
+                "STARTCHAR" => glyphs.push(self.parse_char()?),
+                "ENDFONT" => break,
+                _ => {}
+            }
+        }
+
+        let Some([_, height, _, y_offset]) = bounding_box else {
+            return Err(self.error("missing FONTBOUNDINGBOX"));
+        };
+        let ascent = match ascent {
+            Some(ascent) => ascent,
+            None => self.checked(height.checked_add(y_offset))?,
+        };
+        let descent = match descent {
+            Some(descent) => descent,
+            None => self.checked(y_offset.checked_neg())?,
+        };
+        let line_height = self.checked(ascent.checked_add(descent))?;
+        let mut font = BitmapFont::new(line_height.max(0) as u16);
+        for (ch, glyph) in glyphs.into_iter().flatten() {
+            let image = glyph.image.offset(Position::xy(0, ascent));
+            font.glyphs.insert(ch, Glyph::new(image, glyph.advance));
+        }
+        Ok(font)
+    }
+
+    /// Parses a glyph whose image is relative to the baseline.
+    fn parse_char(&mut self) -> Result<Option<(char, Glyph)>, BdfError> {
+        let mut ch = None;
+        let mut advance = None;
+        let mut bbx = None;
+        let mut pixels = Vec::new();
+        loop {
+            let (keyword, args) = self.next_line()?;
+            match keyword {
+                "ENCODING" => {
+                    let code = self.number(args.split_whitespace().next().unwrap_or(""))?;
+                    ch = u32::try_from(code).ok().and_then(char::from_u32);
+                }
+                "DWIDTH" => advance = Some(self.numbers::<2>(args)?[0]),
+                "BBX" => bbx = Some(self.numbers::<4>(args)?),
+                "BITMAP" => {
+                    let Some([width, height, x_offset, y_offset]) = bbx else {
+                        return Err(self.error("BITMAP before BBX"));
+                    };
+                    for row in 0..height {
+                        let (bits, _) = self.next_line()?;
+                        let digits = bits
+                            .chars()
+                            .map(|c| c.to_digit(16))
+                            .collect::<Option<Vec<_>>>()
+                            .filter(|d| d.len() * 4 >= width.max(0) as usize)
+                            .ok_or_else(|| self.error(&format!("invalid bitmap row {bits:?}")))?;
+                        for x in 0..width {
+                            let digit = digits[x as usize / 4];
+                            if digit & (8 >> (x % 4)) != 0 {
+                                let y = row
+                                    .checked_sub(height)
+                                    .and_then(|y| y.checked_sub(y_offset));
+                                let position = Position::xy(
+                                    self.checked(x.checked_add(x_offset))?,
+                                    self.checked(y)?,
+                                );
+                                pixels.push(Pixel::new(position, Color::BLACK));
+                            }
+                        }
+                    }
+                }
+                "ENDCHAR" => break,
+                "STARTCHAR" | "ENDFONT" => return Err(self.error("missing ENDCHAR")),
+                _ => {}
+            }
+        }
+
+        let Some([width, ..]) = bbx else {
+            return Err(self.error("missing BBX"));
+        };
+        let advance = advance.unwrap_or(width).max(0) as u16;
+        let image = pixels.into_iter().collect::<Image>();
+        Ok(ch.map(|ch| (ch, Glyph::new(image, advance))))
+    }
+
+    fn next_line(&mut self) -> Result<(&'a str, &'a str), BdfError> {
+        loop {
+            let Some((i, line)) = self.lines.next() else {
+                return Err(self.error("unexpected end of file"));
+            };
+            self.line = i + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            return Ok(line.split_once(' ').unwrap_or((line, "")));
+        }
+    }
+
+    fn numbers<const N: usize>(&self, args: &str) -> Result<[i16; N], BdfError> {
+        let mut values = [0; N];
+        let mut args = args.split_whitespace();
+        for value in &mut values {
+            let arg = args
+                .next()
+                .ok_or_else(|| self.error(&format!("expected {N} numbers")))?;
+            *value = i16::try_from(self.number(arg)?)
+                .map_err(|_| self.error(&format!("number {arg:?} out of range")))?;
+        }
+        Ok(values)
+    }
+
+    /// Returns the result of checked arithmetic, failing at the current line if it overflowed.
+    fn checked(&self, value: Option<i16>) -> Result<i16, BdfError> {
+        value.ok_or_else(|| self.error("coordinate out of range"))
+    }
+
+    fn number(&self, arg: &str) -> Result<i32, BdfError> {
+        arg.parse()
+            .map_err(|_| self.error(&format!("invalid number {arg:?}")))
+    }
+
+    fn error(&self, reason: &str) -> BdfError {
+        BdfError::Malformed {
+            line: self.line,
+            reason: reason.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: &str = "\
+STARTFONT 2.1
+FONT -test-
+SIZE 6 75 75
+FONTBOUNDINGBOX 4 6 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 5
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR A
+ENCODING 65
+SWIDTH 500 0
+DWIDTH 4 0
+BBX 3 3 0 0
+BITMAP
+40
+A0
+E0
+ENDCHAR
+STARTCHAR comma
+ENCODING 44
+DWIDTH 2 0
+BBX 1 2 0 -1
+BITMAP
+80
+80
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn read_from_works() {
+        let font = BdfFont::read_from(FONT.as_bytes()).unwrap().into_font();
+        assert_eq!(font.line_height, 6);
+
+        let glyph = &font.glyphs[&'A'];
+        assert_eq!(glyph.advance, 4);
+        assert_eq!(
+            glyph.image.pixels.keys().copied().collect::<Vec<_>>(),
+            [
+                Position::xy(1, 2),
+                Position::xy(0, 3),
+                Position::xy(2, 3),
+                Position::xy(0, 4),
+                Position::xy(1, 4),
+                Position::xy(2, 4),
+            ]
+        );
+
+        let comma = &font.glyphs[&','];
+        assert_eq!(
+            comma.image.pixels.keys().copied().collect::<Vec<_>>(),
+            [Position::xy(0, 4), Position::xy(0, 5)]
+        );
+    }
+
+    #[test]
+    fn read_from_reports_line() {
+        let text = FONT.replace("A0", "Z0");
+        let error = BdfFont::read_from(text.as_bytes()).unwrap_err();
+        assert!(matches!(error, BdfError::Malformed { line: 17, .. }));
+
+        let error = BdfFont::read_from("STARTFONT 2.1\n".as_bytes()).unwrap_err();
+        assert!(matches!(error, BdfError::Malformed { line: 1, .. }));
+
+        let text = FONT.replace(
+            "FONTBOUNDINGBOX 4 6 0 -1",
+            "FONTBOUNDINGBOX 4 2147483647 0 1",
+        );
+        let error = BdfFont::read_from(text.as_bytes()).unwrap_err();
+        assert!(matches!(error, BdfError::Malformed { line: 4, .. }));
+
+        let text = FONT.replace("BBX 3 3 0 0", "BBX 3 3 0 32767");
+        let error = BdfFont::read_from(text.as_bytes()).unwrap_err();
+        assert!(matches!(error, BdfError::Malformed { line: 16, .. }));
+    }
+}
@@ -257,6 +257,14 @@ impl Color {
         (self.r, self.g, self.b) = f(self.r, self.g, self.b);
         self
     }
+
+    /// Returns `true` if no channel (including alpha) differs from `other` by more than `tolerance`.
+    pub fn is_similar(self, other: Self, tolerance: u8) -> bool {
+        self.r.abs_diff(other.r) <= tolerance
+            && self.g.abs_diff(other.g) <= tolerance
+            && self.b.abs_diff(other.b) <= tolerance
+            && self.a.abs_diff(other.a) <= tolerance
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -1,12 +1,26 @@
+mod dither;
+mod drop_shadow;
 mod erase;
 mod fill;
+mod flood_fill;
+mod outline;
+mod recolor;
+mod rot_sprite;
 mod scale;
 mod scoped;
 mod silhouette;
+mod transform;
 
+pub use self::dither::{Dither, DitherMethod};
+pub use self::drop_shadow::DropShadow;
 pub use self::erase::Erase;
 pub use self::fill::Fill;
+pub use self::flood_fill::{Connectivity, FloodFill};
+pub use self::outline::{Outline, OutlineMode};
+pub use self::recolor::{Palettize, Recolor, RecolorWith};
+pub use self::rot_sprite::RotSprite;
 pub use self::scale::Scale;
 pub use self::scoped::Scoped;
 pub use self::silhouette::Silhouette;
+pub use self::transform::{Pivot, Transform};
 pub use crate::filter::Filter;
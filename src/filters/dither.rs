@@ -0,0 +1,222 @@
+use crate::{filters::Filter, quantize, Color, Image, Pixel};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DitherMethod {
+    /// Ordered dithering with a 2x2 Bayer matrix.
+    Bayer2,
+
+    /// Ordered dithering with a 4x4 Bayer matrix.
+    Bayer4,
+
+    /// Ordered dithering with an 8x8 Bayer matrix.
+    Bayer8,
+
+    /// Error diffusion distributing all of the error to four neighbors.
+    #[default]
+    FloydSteinberg,
+
+    /// Error diffusion distributing three quarters of the error to six neighbors.
+    Atkinson,
+}
+
+/// Reduces the colors of the composited image to a palette, dithering the quantization error.
+///
+/// The image is flattened: its children are merged into its own pixels. Transparent pixels are
+/// left as they are and do not take part in error diffusion.
+#[derive(Debug, Clone)]
+pub struct Dither {
+    palette: Vec<Color>,
+    method: DitherMethod,
+}
+
+impl Dither {
+    pub fn new(palette: impl IntoIterator<Item = Color>) -> Self {
+        Self {
+            palette: palette.into_iter().collect(),
+            method: DitherMethod::default(),
+        }
+    }
+
+    pub fn method(self, method: DitherMethod) -> Self {
+        Self { method, ..self }
+    }
+
+    fn nearest(&self, rgb: [f32; 3], alpha: u8) -> Color {
+        let [r, g, b] = rgb.map(|v| v.round().clamp(0.0, 255.0) as u8);
+        let color = Color::rgba(r, g, b, alpha);
+        quantize::nearest_index(&self.palette, color).map_or(color, |i| self.palette[i])
+    }
+
+    /// Returns the typical per-channel distance between neighboring palette colors.
+    fn spread(&self) -> f32 {
+        let distances = self
+            .palette
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &a)| {
+                self.palette
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, &b)| i != j && a != b)
+                    .map(|(_, &b)| quantize::distance(a, b))
+                    .min()
+            })
+            .map(|d| (d as f32 / 3.0).sqrt())
+            .collect::<Vec<_>>();
+        if distances.is_empty() {
+            0.0
+        } else {
+            distances.iter().sum::<f32>() / distances.len() as f32
+        }
+    }
+
+    fn ordered(&self, pixels: &mut [Pixel], n: usize) {
+        let matrix = bayer_matrix(n);
+        let spread = self.spread();
+        for pixel in pixels {
+            let x = pixel.position.x.rem_euclid(n as i16) as usize;
+            let y = pixel.position.y.rem_euclid(n as i16) as usize;
+            let threshold = (matrix[y * n + x] as f32 + 0.5) / (n * n) as f32 - 0.5;
+            let Color { r, g, b, a } = pixel.color;
+            let rgb = [r, g, b].map(|v| f32::from(v) + threshold * spread);
+            pixel.color = self.nearest(rgb, a);
+        }
+    }
+
+    fn diffuse(&self, pixels: &mut [Pixel], kernel: &[(i16, i16, f32)]) {
+        let index = pixels
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.position, i))
+            .collect::<std::collections::BTreeMap<_, _>>();
+        let mut errors = vec![[0.0f32; 3]; pixels.len()];
+
+        // `pixels` is sorted in row-major order, so errors only flow to unvisited pixels.
+        for i in 0..pixels.len() {
+            let Color { r, g, b, a } = pixels[i].color;
+            let (channels, error) = ([r, g, b], errors[i]);
+            let rgb = [0, 1, 2].map(|c| f32::from(channels[c]) + error[c]);
+            let color = self.nearest(rgb, a);
+            let error = [
+                rgb[0] - f32::from(color.r),
+                rgb[1] - f32::from(color.g),
+                rgb[2] - f32::from(color.b),
+            ];
+            pixels[i].color = color;
+
+            for &(dx, dy, weight) in kernel {
+                let Some(&j) = index.get(&pixels[i].position.move_xy(dx, dy)) else {
+                    continue;
+                };
+                for (e, d) in errors[j].iter_mut().zip(error) {
+                    *e += d * weight;
+                }
+            }
+        }
+    }
+}
+
+impl Filter for Dither {
+    fn filter(&self, image: &mut Image) {
+        let mut pixels = image
+            .get_region()
+            .positions()
+            .map(|p| Pixel::new(p, image.get_color(p)))
+            .filter(|p| !p.color.is_transparent())
+            .collect::<Vec<_>>();
+        pixels.sort_by_key(|p| (p.position.y, p.position.x));
+
+        match self.method {
+            DitherMethod::Bayer2 => self.ordered(&mut pixels, 2),
+            DitherMethod::Bayer4 => self.ordered(&mut pixels, 4),
+            DitherMethod::Bayer8 => self.ordered(&mut pixels, 8),
+            DitherMethod::FloydSteinberg => self.diffuse(
+                &mut pixels,
+                &[
+                    (1, 0, 7.0 / 16.0),
+                    (-1, 1, 3.0 / 16.0),
+                    (0, 1, 5.0 / 16.0),
+                    (1, 1, 1.0 / 16.0),
+                ],
+            ),
+            DitherMethod::Atkinson => self.diffuse(
+                &mut pixels,
+                &[
+                    (1, 0, 1.0 / 8.0),
+                    (2, 0, 1.0 / 8.0),
+                    (-1, 1, 1.0 / 8.0),
+                    (0, 1, 1.0 / 8.0),
+                    (1, 1, 1.0 / 8.0),
+                    (0, 2, 1.0 / 8.0),
+                ],
+            ),
+        }
+
+        image.children.clear();
+        image.pixels = pixels.into_iter().map(|p| (p.position, p.color)).collect();
+    }
+}
+
+/// Returns the `n`x`n` Bayer matrix in row-major order (`n` must be a power of two).
+fn bayer_matrix(n: usize) -> Vec<u32> {
+    let mut matrix = vec![0];
+    let mut size = 1;
+    while size < n {
+        let mut next = vec![0; size * size * 4];
+        for y in 0..size {
+            for x in 0..size {
+                let v = matrix[y * size + x] * 4;
+                next[y * size * 2 + x] = v;
+                next[y * size * 2 + x + size] = v + 2;
+                next[(y + size) * size * 2 + x] = v + 3;
+                next[(y + size) * size * 2 + x + size] = v + 1;
+            }
+        }
+        matrix = next;
+        size *= 2;
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Position, Size};
+
+    #[test]
+    fn dither_works() {
+        assert_eq!(bayer_matrix(2), [0, 2, 3, 1]);
+        assert_eq!(bayer_matrix(4)[..4], [0, 8, 2, 10]);
+
+        let gray = Color::rgb(128, 128, 128);
+        let mut image = Size::square(4)
+            .positions()
+            .map(|p| Pixel::new(p, gray))
+            .collect::<Image>();
+        image.pixels.remove(&Position::xy(3, 3));
+        let image = Image::new().child(image);
+        let palette = [Color::BLACK, Color::WHITE];
+
+        let ordered = image
+            .clone()
+            .filter(Dither::new(palette).method(DitherMethod::Bayer2));
+        assert!(ordered.children.is_empty());
+        assert_eq!(ordered.get_color(Position::xy(0, 0)), Color::BLACK);
+        assert_eq!(ordered.get_color(Position::xy(1, 0)), Color::WHITE);
+        assert_eq!(ordered.get_color(Position::xy(0, 1)), Color::WHITE);
+        assert_eq!(ordered.get_color(Position::xy(1, 1)), Color::BLACK);
+        assert_eq!(ordered.get_color(Position::xy(3, 3)), Color::TRANSPARENT);
+
+        for method in [DitherMethod::FloydSteinberg, DitherMethod::Atkinson] {
+            let diffused = image.clone().filter(Dither::new(palette).method(method));
+            assert_eq!(diffused.pixels.len(), 15);
+            assert!(diffused.pixels.values().all(|c| palette.contains(c)));
+            let whites = diffused
+                .pixels
+                .values()
+                .filter(|c| **c == Color::WHITE)
+                .count();
+            assert!((5..=10).contains(&whites), "{method:?}: {whites}");
+        }
+    }
+}
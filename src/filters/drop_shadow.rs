@@ -0,0 +1,140 @@
+use crate::{filters::Filter, Color, Image, Position};
+use std::collections::BTreeSet;
+
+/// Adds a hard shadow of the composited image beneath it.
+///
+/// The image becomes a parent of two children: the shadow layer, named `"shadow"` by default, and
+/// the original content. The root keeps the original name.
+#[derive(Debug, Clone)]
+pub struct DropShadow {
+    name: String,
+    offset: Position,
+    color: Color,
+    length: u16,
+}
+
+impl DropShadow {
+    pub const DEFAULT_NAME: &'static str = "shadow";
+
+    pub fn new() -> Self {
+        Self {
+            name: Self::DEFAULT_NAME.to_owned(),
+            offset: Position::xy(1, 1),
+            color: Color::BLACK,
+            length: 1,
+        }
+    }
+
+    pub fn name(self, name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..self
+        }
+    }
+
+    pub fn offset(self, offset: Position) -> Self {
+        Self { offset, ..self }
+    }
+
+    pub fn color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    /// Extrudes the shadow by repeating it `length` times along the offset ("long shadow").
+    pub fn long(self, length: u16) -> Self {
+        Self { length, ..self }
+    }
+}
+
+impl Default for DropShadow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Filter for DropShadow {
+    fn filter(&self, image: &mut Image) {
+        let positions = image
+            .positions()
+            .filter(|&p| !image.get_color(p).is_transparent())
+            .collect::<BTreeSet<_>>();
+
+        // Overlapping stamps are written once rather than blended, so the shadow color is uniform.
+        let mut shadow = Image::new().name(&self.name);
+        for i in 1..=self.length as i16 {
+            for &p in &positions {
+                shadow.pixels.insert(p + self.offset * i, self.color);
+            }
+        }
+
+        let content = Image {
+            name: None,
+            pixels: std::mem::take(&mut image.pixels),
+            children: std::mem::take(&mut image.children),
+        };
+        image.children = vec![shadow, content];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filters::{Scoped, Silhouette};
+
+    #[test]
+    fn drop_shadow_works() {
+        let image = Image::from_text([('o', Color::RED)], "o\n").name("icon");
+
+        let shadowed = image
+            .clone()
+            .filter(DropShadow::new().color(Color::BLUE.alpha(128)));
+        assert_eq!(shadowed.name.as_deref(), Some("icon"));
+        assert_eq!(shadowed.get_color(Position::xy(0, 0)), Color::RED);
+        assert_eq!(
+            shadowed.get_color(Position::xy(1, 1)),
+            Color::BLUE.alpha(128)
+        );
+
+        let shadowed = image
+            .filter(
+                DropShadow::new()
+                    .color(Color::BLUE)
+                    .offset(Position::xy(1, 0))
+                    .long(3),
+            )
+            .filter(Scoped::new(DropShadow::DEFAULT_NAME, Silhouette));
+        let colors = (0..5)
+            .map(|x| shadowed.get_color(Position::xy(x, 0)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            colors,
+            [
+                Color::RED,
+                Color::BLACK,
+                Color::BLACK,
+                Color::BLACK,
+                Color::TRANSPARENT
+            ]
+        );
+
+        // Overlapping stamps and layers must not darken the shadow.
+        let layered = Image::from_text([('o', Color::RED)], "oo\n")
+            .child(Image::from_text([('o', Color::GREEN)], "o\n"));
+        let shadowed = layered.filter(
+            DropShadow::new()
+                .color(Color::BLACK.alpha(100))
+                .offset(Position::xy(1, 0))
+                .long(3),
+        );
+        let shadow = &shadowed.children[0];
+        assert_eq!(shadow.pixels.len(), 4);
+        assert!(shadow
+            .pixels
+            .values()
+            .all(|c| *c == Color::BLACK.alpha(100)));
+        assert_eq!(
+            shadowed.get_color(Position::xy(4, 0)),
+            Color::BLACK.alpha(100)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{filters::Filter, Color, Image, Position};
+use std::collections::BTreeSet;
+
+/// Which neighbors of a pixel are considered adjacent to it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, down, left and right.
+    #[default]
+    Four,
+
+    /// `Four` plus the diagonal neighbors.
+    Eight,
+}
+
+impl Connectivity {
+    pub(crate) fn neighbors(self, p: Position) -> impl Iterator<Item = Position> {
+        let diagonals = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+        [(0, -1), (-1, 0), (1, 0), (0, 1)]
+            .into_iter()
+            .chain(diagonals.into_iter().filter(move |_| self == Self::Eight))
+            .map(move |(x, y)| p.move_xy(x, y))
+    }
+}
+
+/// Replaces the contiguous area of similar colors around a seed position, like a paint bucket.
+///
+/// Colors are compared as composited by [`Image::get_color()`], and the area is limited to the
+/// region of the image. Filled pixels are written to the root image and removed from its children
+/// so that the replacement color is what is visible.
+#[derive(Debug, Clone, Copy)]
+pub struct FloodFill {
+    seed: Position,
+    color: Color,
+    connectivity: Connectivity,
+    tolerance: u8,
+}
+
+impl FloodFill {
+    pub const fn new(seed: Position, color: Color) -> Self {
+        Self {
+            seed,
+            color,
+            connectivity: Connectivity::Four,
+            tolerance: 0,
+        }
+    }
+
+    pub const fn connectivity(self, connectivity: Connectivity) -> Self {
+        Self {
+            connectivity,
+            ..self
+        }
+    }
+
+    /// Sets the maximum per-channel difference from the seed color of the pixels to be filled.
+    pub const fn tolerance(self, tolerance: u8) -> Self {
+        Self { tolerance, ..self }
+    }
+}
+
+impl Filter for FloodFill {
+    fn filter(&self, image: &mut Image) {
+        let region = image.get_region();
+        if !region.contains(self.seed) {
+            return;
+        }
+
+        let target = image.get_color(self.seed);
+        let mut stack = vec![self.seed];
+        let mut area = BTreeSet::new();
+        while let Some(position) = stack.pop() {
+            if area.contains(&position)
+                || !region.contains(position)
+                || !image.get_color(position).is_similar(target, self.tolerance)
+            {
+                continue;
+            }
+            area.insert(position);
+            stack.extend(self.connectivity.neighbors(position));
+        }
+
+        for position in area {
+            remove_from_children(image, position);
+            if self.color.is_transparent() {
+                image.pixels.remove(&position);
+            } else {
+                image.pixels.insert(position, self.color);
+            }
+        }
+    }
+}
+
+fn remove_from_children(image: &mut Image, position: Position) {
+    for child in &mut image.children {
+        child.pixels.remove(&position);
+        remove_from_children(child, position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flood_fill_works() {
+        let palette = [('#', Color::BLACK), ('o', Color::rgb(10, 0, 0))];
+        let ring = Image::from_text(palette, "#####\n#  o#\n# # #\n##  #\n#####\n");
+        let image = Image::new().child(ring);
+
+        let filled = image
+            .clone()
+            .filter(FloodFill::new(Position::xy(1, 1), Color::RED));
+        assert_eq!(filled.get_color(Position::xy(2, 1)), Color::RED);
+        assert_eq!(filled.get_color(Position::xy(3, 1)), Color::rgb(10, 0, 0));
+        assert_eq!(filled.get_color(Position::xy(2, 3)), Color::TRANSPARENT);
+        assert_eq!(filled.get_color(Position::xy(0, 0)), Color::BLACK);
+
+        let filled = image.clone().filter(
+            FloodFill::new(Position::xy(1, 1), Color::RED).connectivity(Connectivity::Eight),
+        );
+        assert_eq!(filled.get_color(Position::xy(2, 3)), Color::RED);
+
+        let filled = image.filter(FloodFill::new(Position::xy(3, 1), Color::RED).tolerance(10));
+        assert_eq!(filled.get_color(Position::xy(3, 1)), Color::RED);
+        assert_eq!(filled.get_color(Position::xy(0, 0)), Color::RED);
+        assert_eq!(filled.get_color(Position::xy(1, 1)), Color::TRANSPARENT);
+    }
+}
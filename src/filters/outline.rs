@@ -0,0 +1,154 @@
+use crate::{
+    filters::{Connectivity, Filter},
+    Color, Image, Position,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineMode {
+    /// Adds pixels around the non-transparent pixels.
+    #[default]
+    Outer,
+
+    /// Recolors the non-transparent pixels on the edge.
+    Inner,
+}
+
+/// Draws a border along the edges of the non-transparent pixels of each image in the hierarchy.
+///
+/// Outer outlines are only added where the composited image is transparent, so that the outline
+/// of a layer does not cover other layers.
+#[derive(Debug, Clone, Copy)]
+pub struct Outline {
+    color: Color,
+    mode: OutlineMode,
+    connectivity: Connectivity,
+    sel_out: Option<f32>,
+}
+
+impl Outline {
+    pub const fn new(color: Color) -> Self {
+        Self {
+            color,
+            mode: OutlineMode::Outer,
+            connectivity: Connectivity::Four,
+            sel_out: None,
+        }
+    }
+
+    pub const fn mode(self, mode: OutlineMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    pub const fn connectivity(self, connectivity: Connectivity) -> Self {
+        Self {
+            connectivity,
+            ..self
+        }
+    }
+
+    /// Colors each outline pixel with the adjacent fill color instead of the flat color,
+    /// with its HSV value multiplied by `brightness`.
+    pub const fn sel_out(self, brightness: f32) -> Self {
+        Self {
+            sel_out: Some(brightness),
+            ..self
+        }
+    }
+
+    fn outline(&self, image: &mut Image, composited: &BTreeSet<Position>) {
+        let is_opaque = |p: &Position| image.pixels.get(p).is_some_and(|c| !c.is_transparent());
+
+        let mut outline = BTreeMap::new();
+        for (&position, &color) in &image.pixels {
+            if color.is_transparent() {
+                continue;
+            }
+            match self.mode {
+                OutlineMode::Outer => {
+                    for neighbor in self.connectivity.neighbors(position) {
+                        if !composited.contains(&neighbor) {
+                            outline
+                                .entry(neighbor)
+                                .or_insert_with(|| self.outline_color(color));
+                        }
+                    }
+                }
+                OutlineMode::Inner => {
+                    if !self.connectivity.neighbors(position).all(|p| is_opaque(&p)) {
+                        outline.insert(position, self.outline_color(color));
+                    }
+                }
+            }
+        }
+        image.pixels.extend(outline);
+
+        for child in &mut image.children {
+            self.outline(child, composited);
+        }
+    }
+
+    fn outline_color(&self, fill: Color) -> Color {
+        match self.sel_out {
+            Some(brightness) => fill.map_hsv(|h, s, v| (h, s, v * brightness)),
+            None => self.color,
+        }
+    }
+}
+
+impl Filter for Outline {
+    fn filter(&self, image: &mut Image) {
+        let composited = image
+            .positions()
+            .filter(|&p| !image.get_color(p).is_transparent())
+            .collect::<BTreeSet<_>>();
+        self.outline(image, &composited);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outline_works() {
+        let palette = [('#', Color::BLACK), ('o', Color::RED), ('x', Color::WHITE)];
+        let image = Image::new().child(Image::from_text(palette, "   \n o \n   \n"));
+
+        let outer = image.clone().filter(Outline::new(Color::WHITE));
+        assert_eq!(
+            outer,
+            Image::new().child(Image::from_text(palette, " x \nxox\n x \n"))
+        );
+
+        let outer = image
+            .clone()
+            .filter(Outline::new(Color::WHITE).connectivity(Connectivity::Eight));
+        assert_eq!(
+            outer,
+            Image::new().child(Image::from_text(palette, "xxx\nxox\nxxx\n"))
+        );
+
+        let square = Image::from_text(palette, "ooo\nooo\nooo\n");
+        let inner = square
+            .filter(Outline::new(Color::WHITE).mode(OutlineMode::Inner))
+            .filter(
+                Outline::new(Color::WHITE)
+                    .mode(OutlineMode::Inner)
+                    .sel_out(0.5),
+            );
+        assert_eq!(inner.get_color(Position::xy(1, 1)), Color::RED);
+        assert_eq!(
+            inner.get_color(Position::xy(0, 0)),
+            Color::rgb(128, 128, 128)
+        );
+
+        let body = Image::from_text(palette, "ooooo\nooooo\nooooo\nooooo\nooooo\n");
+        let eye = Image::from_text([('#', Color::BLUE)], "  \n  \n  #\n");
+        let outer = body.child(eye.clone()).filter(Outline::new(Color::WHITE));
+        assert_eq!(outer.get_color(Position::xy(1, 2)), Color::RED);
+        assert_eq!(outer.get_color(Position::xy(2, 2)), Color::BLUE);
+        assert_eq!(outer.get_color(Position::xy(-1, 2)), Color::WHITE);
+        assert_eq!(outer.children[0], eye);
+    }
+}
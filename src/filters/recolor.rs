@@ -0,0 +1,157 @@
+use crate::{filters::Filter, quantize, Color, Image};
+use std::collections::BTreeMap;
+
+/// Replaces colors according to a mapping (palette swap).
+#[derive(Debug, Default, Clone)]
+pub struct Recolor {
+    mapping: BTreeMap<Color, Color>,
+    tolerance: u8,
+}
+
+impl Recolor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn map(mut self, from: Color, to: Color) -> Self {
+        self.mapping.insert(from, to);
+        self
+    }
+
+    pub fn mapping(mut self, mapping: impl IntoIterator<Item = (Color, Color)>) -> Self {
+        self.mapping.extend(mapping);
+        self
+    }
+
+    /// Also replaces colors whose channels differ from a source color by at most `tolerance`.
+    ///
+    /// If several source colors match, the closest one is used.
+    pub fn tolerance(self, tolerance: u8) -> Self {
+        Self { tolerance, ..self }
+    }
+
+    fn recolor(&self, color: Color) -> Option<Color> {
+        if let Some(&to) = self.mapping.get(&color) {
+            return Some(to);
+        }
+        if self.tolerance == 0 {
+            return None;
+        }
+        self.mapping
+            .iter()
+            .filter(|(from, _)| from.is_similar(color, self.tolerance))
+            .min_by_key(|(from, _)| quantize::distance(**from, color))
+            .map(|(_, to)| *to)
+    }
+}
+
+impl Filter for Recolor {
+    fn filter(&self, image: &mut Image) {
+        for color in image.pixels.values_mut() {
+            if let Some(to) = self.recolor(*color) {
+                *color = to;
+            }
+        }
+        for child in &mut image.children {
+            self.filter(child);
+        }
+    }
+}
+
+/// Replaces each color with the result of a function.
+#[derive(Clone, Copy)]
+pub struct RecolorWith<F>(F);
+
+impl<F: Fn(Color) -> Color> RecolorWith<F> {
+    pub const fn new(f: F) -> Self {
+        Self(f)
+    }
+}
+
+impl<F: Fn(Color) -> Color> Filter for RecolorWith<F> {
+    fn filter(&self, image: &mut Image) {
+        for color in image.pixels.values_mut() {
+            *color = (self.0)(*color);
+        }
+        for child in &mut image.children {
+            self.filter(child);
+        }
+    }
+}
+
+/// Replaces each non-transparent color with the nearest color in a palette.
+#[derive(Debug, Clone)]
+pub struct Palettize {
+    palette: Vec<Color>,
+}
+
+impl Palettize {
+    pub fn new(palette: impl IntoIterator<Item = Color>) -> Self {
+        Self {
+            palette: palette.into_iter().collect(),
+        }
+    }
+}
+
+impl Filter for Palettize {
+    fn filter(&self, image: &mut Image) {
+        for color in image.pixels.values_mut() {
+            if color.is_transparent() {
+                continue;
+            }
+            if let Some(i) = quantize::nearest_index(&self.palette, *color) {
+                *color = self.palette[i];
+            }
+        }
+        for child in &mut image.children {
+            self.filter(child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    #[test]
+    fn recolor_works() {
+        let palette = [
+            ('r', Color::RED),
+            ('g', Color::GREEN),
+            ('d', Color::DARK_RED),
+        ];
+        let image = Image::new().child(Image::from_text(palette, "rgd\n"));
+        let colors = |image: &Image| {
+            (0..3)
+                .map(|x| image.get_color(Position::xy(x, 0)))
+                .collect::<Vec<_>>()
+        };
+
+        let swapped = image
+            .clone()
+            .filter(Recolor::new().map(Color::RED, Color::BLUE));
+        assert_eq!(
+            colors(&swapped),
+            [Color::BLUE, Color::GREEN, Color::DARK_RED]
+        );
+
+        let swapped = image.clone().filter(
+            Recolor::new()
+                .mapping([(Color::RED, Color::BLUE), (Color::BLACK, Color::WHITE)])
+                .tolerance(128),
+        );
+        assert_eq!(colors(&swapped), [Color::BLUE, Color::WHITE, Color::BLUE]);
+
+        let swapped = image
+            .clone()
+            .filter(RecolorWith::new(|c: Color| c.map_rgb(|r, g, b| (b, g, r))));
+        assert_eq!(
+            colors(&swapped),
+            [Color::BLUE, Color::GREEN, Color::rgb(0, 0, 0x8b)]
+        );
+
+        let mapped = image.filter(Palettize::new([Color::BLACK, Color::WHITE, Color::RED]));
+        assert_eq!(colors(&mapped), [Color::RED, Color::BLACK, Color::RED]);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{filters::Filter, Image, Position};
+
+/// Point around which an image is flipped or rotated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Pivot {
+    /// Center of the bounding box of the whole image, so that the image stays in place.
+    ///
+    /// If the center falls between pixels after rotation, the result is shifted up and left
+    /// by half a pixel.
+    #[default]
+    Center,
+
+    /// The pixel at the origin.
+    Origin,
+
+    /// The given pixel.
+    Position(Position),
+}
+
+/// Flips, rotates (clockwise on screen) or transposes an image and all of its children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transform {
+    // Row-major 2x2 matrix.
+    matrix: [i16; 4],
+    pivot: Pivot,
+}
+
+impl Transform {
+    const fn new(matrix: [i16; 4]) -> Self {
+        Self {
+            matrix,
+            pivot: Pivot::Center,
+        }
+    }
+
+    pub const fn flip_horizontal() -> Self {
+        Self::new([-1, 0, 0, 1])
+    }
+
+    pub const fn flip_vertical() -> Self {
+        Self::new([1, 0, 0, -1])
+    }
+
+    pub const fn rotate90() -> Self {
+        Self::new([0, -1, 1, 0])
+    }
+
+    pub const fn rotate180() -> Self {
+        Self::new([-1, 0, 0, -1])
+    }
+
+    pub const fn rotate270() -> Self {
+        Self::new([0, 1, -1, 0])
+    }
+
+    /// Swaps the x and y axes (mirrors along the diagonal from the top-left).
+    pub const fn transpose() -> Self {
+        Self::new([0, 1, 1, 0])
+    }
+
+    pub const fn pivot(self, pivot: Pivot) -> Self {
+        Self { pivot, ..self }
+    }
+
+    fn apply(&self, image: &mut Image, pivot2: (i32, i32)) {
+        let [a, b, c, d] = self.matrix.map(i32::from);
+        let (px, py) = pivot2;
+        image.pixels = std::mem::take(&mut image.pixels)
+            .into_iter()
+            .map(|(p, color)| {
+                // Coordinates are doubled so that the pivot can be between pixels.
+                let (x, y) = (i32::from(p.x) * 2 - px, i32::from(p.y) * 2 - py);
+                let tx = (a * x + b * y + px).div_euclid(2);
+                let ty = (c * x + d * y + py).div_euclid(2);
+                (Position::xy(tx as i16, ty as i16), color)
+            })
+            .collect();
+        for child in &mut image.children {
+            self.apply(child, pivot2);
+        }
+    }
+}
+
+impl Filter for Transform {
+    fn filter(&self, image: &mut Image) {
+        let pivot2 = match self.pivot {
+            Pivot::Center => {
+                let region = image.get_region();
+                if region.is_empty() {
+                    return;
+                }
+                let (start, end) = (region.top_left(), region.bottom_right());
+                (
+                    i32::from(start.x) + i32::from(end.x),
+                    i32::from(start.y) + i32::from(end.y),
+                )
+            }
+            Pivot::Origin => (0, 0),
+            Pivot::Position(p) => (i32::from(p.x) * 2, i32::from(p.y) * 2),
+        };
+        self.apply(image, pivot2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn transform_works() {
+        // The 3x2 region is centered at (2, 1.5), so turning it sideways shifts it by half a pixel.
+        let palette = [('#', Color::BLACK), ('o', Color::RED)];
+        let image = Image::from_text(palette, "#o.\n#..\n").offset(Position::xy(1, 1));
+        let layered = Image::new().child(image.clone());
+        let expected = |text: &str| Image::from_text(palette, text).offset(Position::xy(1, 1));
+
+        let flipped = layered.clone().filter(Transform::flip_horizontal());
+        assert_eq!(flipped.children[0], expected(".o#\n..#\n"));
+        assert_eq!(
+            image.clone().filter(Transform::flip_vertical()),
+            expected("#..\n#o.\n")
+        );
+        assert_eq!(
+            image.clone().filter(Transform::rotate180()),
+            expected("..#\n.o#\n")
+        );
+        assert_eq!(
+            image.clone().filter(Transform::transpose()),
+            Image::from_text(palette, "##\no.\n..\n").offset(Position::xy(1, 0))
+        );
+        assert_eq!(
+            image.clone().filter(Transform::rotate90()),
+            Image::from_text(palette, "##\n.o\n..\n").offset(Position::xy(1, 0))
+        );
+        let restored = image
+            .clone()
+            .filter(Transform::rotate90().pivot(Pivot::Origin))
+            .filter(Transform::rotate270().pivot(Pivot::Origin));
+        assert_eq!(restored, image);
+
+        let rotated =
+            image.filter(Transform::rotate90().pivot(Pivot::Position(Position::xy(1, 1))));
+        assert_eq!(rotated.get_color(Position::xy(1, 1)), Color::BLACK);
+        assert_eq!(rotated.get_color(Position::xy(0, 1)), Color::BLACK);
+        assert_eq!(rotated.get_color(Position::xy(1, 2)), Color::RED);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{Color, Image};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Glyph {
+    pub image: Image,
+
+    /// Horizontal distance from the start of this glyph to the start of the next one.
+    pub advance: u16,
+}
+
+impl Glyph {
+    pub fn new(image: Image, advance: u16) -> Self {
+        Self { image, advance }
+    }
+
+    /// Returns the horizontal distance from the start of this glyph to the right edge of its image.
+    fn get_extent(&self) -> i32 {
+        let region = self.image.get_region();
+        if region.is_empty() {
+            0
+        } else {
+            i32::from(region.right()) + 1
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BitmapFont {
+    pub glyphs: BTreeMap<char, Glyph>,
+    pub line_height: u16,
+
+    /// Adjustments added to the advance of the first character of each pair.
+    pub kerning: BTreeMap<(char, char), i16>,
+}
+
+impl BitmapFont {
+    pub fn new(line_height: u16) -> Self {
+        Self {
+            line_height,
+            ..Self::default()
+        }
+    }
+
+    pub fn glyph(mut self, ch: char, glyph: Glyph) -> Self {
+        self.glyphs.insert(ch, glyph);
+        self
+    }
+
+    pub fn kerning(mut self, pair: (char, char), adjustment: i16) -> Self {
+        self.kerning.insert(pair, adjustment);
+        self
+    }
+
+    /// Returns the glyph for `ch`, or that for `'?'` if the font does not have it.
+    pub fn get_glyph(&self, ch: char) -> Option<&Glyph> {
+        self.glyphs.get(&ch).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    /// Returns the advance of `ch` including the kerning with the following character.
+    pub fn get_advance(&self, ch: char, next: Option<char>) -> i16 {
+        let advance = self.get_glyph(ch).map_or(0, |g| g.advance as i16);
+        let kerning = next
+            .and_then(|next| self.kerning.get(&(ch, next)))
+            .copied()
+            .unwrap_or(0);
+        advance + kerning
+    }
+
+    /// Returns the width of the drawn text, which ends at the right edge of the last glyph
+    /// rather than at its advance.
+    pub fn get_text_width(&self, text: &str) -> u16 {
+        let mut chars = text.chars().peekable();
+        let mut width = 0i32;
+        while let Some(ch) = chars.next() {
+            match chars.peek() {
+                Some(&next) => width += i32::from(self.get_advance(ch, Some(next))),
+                None => width += self.get_glyph(ch).map_or(0, Glyph::get_extent),
+            }
+        }
+        width.clamp(0, i32::from(u16::MAX)) as u16
+    }
+
+    /// Small 3x5 font covering digits, letters (lowercase letters are drawn as uppercase) and
+    /// common punctuation, intended for debugging overlays.
+    pub fn tiny() -> Self {
+        let mut font = Self::new(6);
+        for (ch, rows) in TINY_GLYPHS {
+            let mut image = Image::from_text([('#', Color::BLACK)], &rows.join("\n"));
+            image.pixels.retain(|_, color| !color.is_transparent());
+            let glyph = Glyph::new(image, 4);
+            if ch.is_ascii_uppercase() {
+                font.glyphs.insert(ch.to_ascii_lowercase(), glyph.clone());
+            }
+            font.glyphs.insert(*ch, glyph);
+        }
+        font
+    }
+}
+
+const TINY_GLYPHS: &[(char, [&str; 5])] = &[
+    (' ', ["...", "...", "...", "...", "..."]),
+    ('0', ["###", "#.#", "#.#", "#.#", "###"]),
+    ('1', [".#.", "##.", ".#.", ".#.", "###"]),
+    ('2', ["###", "..#", "###", "#..", "###"]),
+    ('3', ["###", "..#", ".##", "..#", "###"]),
+    ('4', ["#.#", "#.#", "###", "..#", "..#"]),
+    ('5', ["###", "#..", "###", "..#", "###"]),
+    ('6', ["###", "#..", "###", "#.#", "###"]),
+    ('7', ["###", "..#", ".#.", ".#.", ".#."]),
+    ('8', ["###", "#.#", "###", "#.#", "###"]),
+    ('9', ["###", "#.#", "###", "..#", "###"]),
+    ('A', [".#.", "#.#", "###", "#.#", "#.#"]),
+    ('B', ["##.", "#.#", "##.", "#.#", "##."]),
+    ('C', [".##", "#..", "#..", "#..", ".##"]),
+    ('D', ["##.", "#.#", "#.#", "#.#", "##."]),
+    ('E', ["###", "#..", "##.", "#..", "###"]),
+    ('F', ["###", "#..", "##.", "#..", "#.."]),
+    ('G', [".##", "#..", "#.#", "#.#", ".##"]),
+    ('H', ["#.#", "#.#", "###", "#.#", "#.#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..#", "..#", "..#", "#.#", ".#."]),
+    ('K', ["#.#", "#.#", "##.", "#.#", "#.#"]),
+    ('L', ["#..", "#..", "#..", "#..", "###"]),
+    ('M', ["#.#", "###", "###", "#.#", "#.#"]),
+    ('N', ["##.", "#.#", "#.#", "#.#", "#.#"]),
+    ('O', [".#.", "#.#", "#.#", "#.#", ".#."]),
+    ('P', ["##.", "#.#", "##.", "#..", "#.."]),
+    ('Q', [".#.", "#.#", "#.#", "##.", ".##"]),
+    ('R', ["##.", "#.#", "##.", "#.#", "#.#"]),
+    ('S', [".##", "#..", ".#.", "..#", "##."]),
+    ('T', ["###", ".#.", ".#.", ".#.", ".#."]),
+    ('U', ["#.#", "#.#", "#.#", "#.#", "###"]),
+    ('V', ["#.#", "#.#", "#.#", "#.#", ".#."]),
+    ('W', ["#.#", "#.#", "###", "###", "#.#"]),
+    ('X', ["#.#", "#.#", ".#.", "#.#", "#.#"]),
+    ('Y', ["#.#", "#.#", ".#.", ".#.", ".#."]),
+    ('Z', ["###", "..#", ".#.", "#..", "###"]),
+    ('.', ["...", "...", "...", "...", ".#."]),
+    (',', ["...", "...", "...", ".#.", "#.."]),
+    (':', ["...", ".#.", "...", ".#.", "..."]),
+    (';', ["...", ".#.", "...", ".#.", "#.."]),
+    ('!', [".#.", ".#.", ".#.", "...", ".#."]),
+    ('?', ["###", "..#", ".#.", "...", ".#."]),
+    ('-', ["...", "...", "###", "...", "..."]),
+    ('+', ["...", ".#.", "###", ".#.", "..."]),
+    ('=', ["...", "###", "...", "###", "..."]),
+    ('*', ["#.#", ".#.", "#.#", "...", "..."]),
+    ('/', ["..#", "..#", ".#.", "#..", "#.."]),
+    ('%', ["#.#", "..#", ".#.", "#..", "#.#"]),
+    ('(', [".#.", "#..", "#..", "#..", ".#."]),
+    (')', [".#.", "..#", "..#", "..#", ".#."]),
+    ('[', ["##.", "#..", "#..", "#..", "##."]),
+    (']', [".##", "..#", "..#", "..#", ".##"]),
+    ('<', ["..#", ".#.", "#..", ".#.", "..#"]),
+    ('>', ["#..", ".#.", "..#", ".#.", "#.."]),
+    ('\'', [".#.", ".#.", "...", "...", "..."]),
+    ('"', ["#.#", "#.#", "...", "...", "..."]),
+    ('_', ["...", "...", "...", "...", "###"]),
+    ('#', ["#.#", "###", "#.#", "###", "#.#"]),
+];
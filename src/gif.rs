@@ -1,19 +1,32 @@
-use crate::{Animation, Color};
-use std::{collections::BTreeSet, io::Write};
+use crate::{quantize, Animation, Color, Position, Region};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct AnimatedGifImage {
     anime: Animation,
     global_palette: BTreeSet<Color>,
     repeat: bool,
+    alpha_threshold: u8,
+    disposal: gif::DisposalMethod,
+    differencing: bool,
 }
 
 impl AnimatedGifImage {
+    pub const DEFAULT_ALPHA_THRESHOLD: u8 = 128;
+
+    const MAX_COLORS: usize = 256;
+
     pub fn new(anime: Animation) -> Self {
         Self {
             anime,
             global_palette: BTreeSet::new(),
             repeat: false,
+            alpha_threshold: Self::DEFAULT_ALPHA_THRESHOLD,
+            disposal: gif::DisposalMethod::Background,
+            differencing: true,
         }
     }
 
@@ -22,14 +35,87 @@ impl AnimatedGifImage {
         self
     }
 
+    /// Pixels whose alpha is below `threshold` are written as transparent,
+    /// and all the others as opaque (GIF only supports 1-bit transparency).
+    pub fn alpha_threshold(&mut self, threshold: u8) -> &mut Self {
+        self.alpha_threshold = threshold;
+        self
+    }
+
+    /// Sets the disposal method of the frames.
+    ///
+    /// With frame differencing, frames followed by another frame need a specific disposal method,
+    /// so this applies to the last frame only (unless repeating requires it to be cleared).
+    /// With [`AnimatedGifImage::full_frames()`], this applies to every frame.
+    pub fn disposal(&mut self, method: gif::DisposalMethod) -> &mut Self {
+        self.disposal = method;
+        self
+    }
+
+    /// Writes every frame in full instead of only the region that changed from the previous frame.
+    pub fn full_frames(&mut self) -> &mut Self {
+        self.differencing = false;
+        self
+    }
+
+    /// Uses `palette` as the global palette instead of computing one from the frames.
+    ///
+    /// Each pixel is mapped to the nearest color in the palette.
+    /// If some pixels are transparent, an extra palette entry is reserved for them,
+    /// so the palette should have at most 255 colors in that case.
+    pub fn palette(&mut self, palette: impl IntoIterator<Item = Color>) -> &mut Self {
+        self.global_palette = palette.into_iter().map(|c| c.alpha(0xFF)).collect();
+        self
+    }
+
     pub fn write_to<W: Write>(&self, writer: W) -> Result<(), gif::EncodingError> {
+        let frames = self
+            .anime
+            .frames
+            .iter()
+            .map(|frame| {
+                let (size, colors) = frame.to_size_and_colors();
+                let colors = colors
+                    .into_iter()
+                    .map(|c| self.to_binary_alpha(c))
+                    .collect::<Vec<_>>();
+                (size, colors)
+            })
+            .collect::<Vec<_>>();
+        let opaque_colors = frames
+            .iter()
+            .flat_map(|(_, colors)| colors.iter().copied())
+            .filter(|c| !c.is_transparent());
+
+        // Frames smaller than the whole image are padded with transparent pixels.
         let size = self.anime.get_max_frame_size();
+        let has_transparent_pixels = frames.iter().any(|(frame_size, colors)| {
+            *frame_size != size || colors.iter().any(|c| c.is_transparent())
+        });
+
+        let mut palette = if self.global_palette.is_empty() {
+            let max_colors = Self::MAX_COLORS - usize::from(has_transparent_pixels);
+            let unique_colors = opaque_colors.clone().collect::<BTreeSet<_>>();
+            if unique_colors.len() <= max_colors {
+                unique_colors.into_iter().collect()
+            } else {
+                median_cut(opaque_colors, max_colors)
+            }
+        } else {
+            self.global_palette.iter().copied().collect::<Vec<_>>()
+        };
+        let transparent = if has_transparent_pixels {
+            palette.push(Color::TRANSPARENT);
+            Some((palette.len() - 1) as u8)
+        } else {
+            None
+        };
+
         let mut encoder = gif::Encoder::new(
             writer,
             size.width,
             size.height,
-            &self
-                .global_palette
+            &palette
                 .iter()
                 .flat_map(|c| [c.r, c.g, c.b].into_iter())
                 .collect::<Vec<_>>(),
@@ -39,22 +125,319 @@ impl AnimatedGifImage {
             encoder.set_repeat(gif::Repeat::Infinite)?;
         }
 
-        let delay = self.anime.get_frame_duration().as_millis() as u16 / 10;
-        for frame in &self.anime.frames {
-            let (size, colors) = frame.to_size_and_colors();
-            let mut frame = gif::Frame::from_rgb(
-                size.width,
-                size.height,
-                &colors
+        let opaque_palette = &palette[..palette.len() - usize::from(transparent.is_some())];
+        let mut indices = BTreeMap::new();
+        let mut canvases = Vec::<(Vec<u8>, u16)>::new();
+        for (n, (frame_size, colors)) in frames.into_iter().enumerate() {
+            let delay = self.anime.get_nth_frame_duration(n).as_millis() / 10;
+            let delay = u16::try_from(delay).unwrap_or(u16::MAX);
+            // Frames may be smaller than the whole image, so they are placed on a canvas first.
+            let mut canvas = vec![transparent.unwrap_or(0); size.area() as usize];
+            for (i, color) in colors.into_iter().enumerate() {
+                let x = i % usize::from(frame_size.width);
+                let y = i / usize::from(frame_size.width);
+                canvas[y * usize::from(size.width) + x] = match transparent {
+                    Some(i) if color.is_transparent() => i,
+                    _ => *indices.entry(color).or_insert_with(|| {
+                        quantize::nearest_index(opaque_palette, color).unwrap_or(0) as u8
+                    }),
+                };
+            }
+            match canvases.last_mut() {
+                Some((prev, prev_delay)) if self.differencing && *prev == canvas => {
+                    *prev_delay = prev_delay.saturating_add(delay);
+                }
+                _ => canvases.push((canvas, delay)),
+            }
+        }
+        if canvases.is_empty() {
+            return Ok(());
+        }
+
+        let full_region = Region::new(Position::ORIGIN, size);
+        let mut regions = vec![full_region; canvases.len()];
+        let mut disposals = vec![self.disposal; canvases.len()];
+        if self.differencing {
+            let is_transparent = |i: u8| Some(i) == transparent;
+            for i in 1..canvases.len() {
+                let (prev, _) = &canvases[i - 1];
+                let (curr, _) = &canvases[i];
+                let changed = full_region
+                    .positions()
+                    .zip(prev.iter().zip(curr))
+                    .filter(|(_, (a, b))| a != b)
+                    .map(|(p, (_, b))| (p, *b))
+                    .collect::<Vec<_>>();
+                let cleared = changed
                     .iter()
-                    .flat_map(|c| [c.r, c.g, c.b].into_iter())
-                    .collect::<Vec<_>>(),
+                    .filter(|(_, b)| is_transparent(*b))
+                    .map(|(p, _)| *p)
+                    .collect::<Region>();
+                let changed = changed.into_iter().map(|(p, _)| p).collect::<Region>();
+
+                if cleared.is_empty() {
+                    disposals[i - 1] = gif::DisposalMethod::Keep;
+                    regions[i] = changed;
+                } else {
+                    // Drawing a transparent pixel leaves the previous one as it is,
+                    // so such pixels need to be cleared by disposing the previous frame.
+                    regions[i - 1] = union(regions[i - 1], cleared);
+                    disposals[i - 1] = gif::DisposalMethod::Background;
+                    regions[i] = union(regions[i - 1], changed);
+                }
+            }
+
+            // Nothing is drawn on top of the last frame, except for the first frame when repeating.
+            let last = canvases.len() - 1;
+            disposals[last] = self.disposal;
+            if self.repeat && canvases.len() > 1 {
+                // Pixels left by the last frame (or by the one before it, if it is restored)
+                // would show through the transparent pixels of the first frame.
+                let (first, _) = &canvases[0];
+                let shows_through = |(canvas, _): &(Vec<u8>, u16)| {
+                    first
+                        .iter()
+                        .zip(canvas)
+                        .any(|(a, b)| is_transparent(*a) && !is_transparent(*b))
+                };
+                if shows_through(&canvases[last])
+                    || (self.disposal == gif::DisposalMethod::Previous
+                        && shows_through(&canvases[last - 1]))
+                {
+                    regions[last] = full_region;
+                    disposals[last] = gif::DisposalMethod::Background;
+                }
+            }
+        }
+
+        for (((canvas, delay), region), disposal) in
+            canvases.into_iter().zip(regions).zip(disposals)
+        {
+            let pixels = region
+                .positions()
+                .map(|p| canvas[p.y as usize * usize::from(size.width) + p.x as usize])
+                .collect::<Vec<_>>();
+            let mut frame = gif::Frame::from_indexed_pixels(
+                region.size.width,
+                region.size.height,
+                &pixels,
+                transparent,
             );
+            frame.left = region.start.x as u16;
+            frame.top = region.start.y as u16;
             frame.delay = delay;
-            frame.palette = None;
+            frame.dispose = disposal;
             encoder.write_frame(&frame)?;
         }
 
         Ok(())
     }
+
+    fn to_binary_alpha(&self, color: Color) -> Color {
+        if color.a < self.alpha_threshold {
+            // All transparent pixels share a single palette index.
+            Color::TRANSPARENT
+        } else {
+            color.alpha(0xFF)
+        }
+    }
+}
+
+/// Reduces `colors` to at most `max_colors` colors using the median cut algorithm in RGBA space.
+fn median_cut(colors: impl IntoIterator<Item = Color>, max_colors: usize) -> Vec<Color> {
+    let to_rgba = |color: Color| [color.r, color.g, color.b, color.a].map(f64::from);
+    let from_rgba = |point: quantize::Point| {
+        let [r, g, b, a] = point.map(|v| v.round().clamp(0.0, 255.0) as u8);
+        Color::rgba(r, g, b, a)
+    };
+    quantize::median_cut_with(quantize::histogram(colors), max_colors, to_rgba, from_rgba)
+}
+
+fn union(a: Region, b: Region) -> Region {
+    [a, b]
+        .into_iter()
+        .filter(|r| !r.is_empty())
+        .flat_map(|r| [r.top_left(), r.bottom_right()])
+        .collect()
+}
+
+impl Default for AnimatedGifImage {
+    fn default() -> Self {
+        Self::new(Animation::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Image, Pixel, Size};
+
+    #[test]
+    fn transparent_pixels_work() {
+        let image = Image::from_text(
+            [('#', Color::BLACK), ('o', Color::RED.alpha(64))],
+            "#o\n #\n",
+        );
+        let mut bytes = Vec::new();
+        AnimatedGifImage::new(Animation::still_frame(image))
+            .full_frames()
+            .write_to(&mut bytes)
+            .expect("failed to encode");
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(bytes.as_slice()).expect("invalid header");
+        let frame = decoder
+            .read_next_frame()
+            .expect("invalid frame")
+            .expect("no frame");
+        assert_eq!(frame.dispose, gif::DisposalMethod::Background);
+        assert!(frame.transparent.is_some());
+        let alphas = frame
+            .buffer
+            .chunks_exact(4)
+            .map(|c| c[3])
+            .collect::<Vec<_>>();
+        assert_eq!(alphas, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn empty_animation_works() {
+        let mut bytes = Vec::new();
+        AnimatedGifImage::default()
+            .write_to(&mut bytes)
+            .expect("failed to encode");
+
+        // The decoder rejects GIFs without frames, so only the header and the trailer are checked.
+        let decoder = gif::DecodeOptions::new()
+            .read_info(bytes.as_slice())
+            .expect("invalid header");
+        assert_eq!((decoder.width(), decoder.height()), (0, 0));
+        assert_eq!(bytes.last(), Some(&0x3B));
+    }
+
+    #[test]
+    fn smaller_frames_are_padded_with_transparent_pixels() {
+        let palette = [('r', Color::RED), ('b', Color::BLUE)];
+        let anime = Animation::new().frames([
+            Image::from_text(palette, "rr\nrr\n"),
+            Image::from_text(palette, "b\n"),
+        ]);
+        let mut bytes = Vec::new();
+        AnimatedGifImage::new(anime)
+            .full_frames()
+            .disposal(gif::DisposalMethod::Keep)
+            .write_to(&mut bytes)
+            .expect("failed to encode");
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(bytes.as_slice()).expect("invalid header");
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().expect("invalid frame") {
+            assert_eq!(frame.dispose, gif::DisposalMethod::Keep);
+            let colors = frame
+                .buffer
+                .chunks_exact(4)
+                .map(|c| Color::rgba(c[0], c[1], c[2], c[3]))
+                .map(|c| if c.a == 0 { Color::TRANSPARENT } else { c })
+                .collect::<Vec<_>>();
+            frames.push(colors);
+        }
+        assert_eq!(
+            frames,
+            [
+                vec![Color::RED; 4],
+                vec![
+                    Color::BLUE,
+                    Color::TRANSPARENT,
+                    Color::TRANSPARENT,
+                    Color::TRANSPARENT
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn global_palette_works() {
+        let gradient = (0..32)
+            .flat_map(|y| (0..32).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let color = Color::rgb(x as u8 * 8, y as u8 * 8, 128);
+                Pixel::new(Position::xy(x, y), color)
+            })
+            .collect::<Image>();
+        let anime = Animation::new().frame(gradient.clone()).frame(gradient);
+
+        let mut bytes = Vec::new();
+        AnimatedGifImage::new(anime)
+            .write_to(&mut bytes)
+            .expect("failed to encode");
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(bytes.as_slice())
+            .expect("invalid header");
+        assert_eq!(decoder.global_palette().map(|p| p.len()), Some(256 * 3));
+        while let Some(frame) = decoder.read_next_frame().expect("invalid frame") {
+            assert!(frame.palette.is_none());
+        }
+    }
+
+    #[test]
+    fn frame_differencing_works() {
+        let palette = [('#', Color::BLACK), ('o', Color::RED)];
+        let frames = [
+            "####\n#oo#\n####\n",
+            "####\n#oo#\n####\n",
+            "####\n#o##\n####\n",
+            "####\n#o #\n####\n",
+        ]
+        .map(|text| Image::from_text(palette, text));
+        let anime = Animation::new().frames(frames.clone()).fps10();
+
+        let mut bytes = Vec::new();
+        AnimatedGifImage::new(anime)
+            .write_to(&mut bytes)
+            .expect("failed to encode");
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(bytes.as_slice()).expect("invalid header");
+        let mut canvas = vec![Color::TRANSPARENT; 12];
+        let mut expected = [&frames[0], &frames[2], &frames[3]].into_iter();
+        let mut rects = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().expect("invalid frame") {
+            let region = Region::new(
+                Position::xy(frame.left as i16, frame.top as i16),
+                Size::new(frame.width, frame.height),
+            );
+            for (p, c) in region.positions().zip(frame.buffer.chunks_exact(4)) {
+                if c[3] != 0 {
+                    canvas[p.y as usize * 4 + p.x as usize] = Color::rgba(c[0], c[1], c[2], c[3]);
+                }
+            }
+            assert_eq!(
+                canvas,
+                expected
+                    .next()
+                    .expect("too many frames")
+                    .to_size_and_colors()
+                    .1
+            );
+            rects.push((region, frame.delay));
+            if frame.dispose == gif::DisposalMethod::Background {
+                for p in region.positions() {
+                    canvas[p.y as usize * 4 + p.x as usize] = Color::TRANSPARENT;
+                }
+            }
+        }
+        assert_eq!(
+            rects,
+            [
+                (Region::new(Position::ORIGIN, Size::new(4, 3)), 20),
+                (Region::new(Position::xy(2, 1), Size::new(1, 1)), 10),
+                (Region::new(Position::xy(2, 1), Size::new(1, 1)), 10),
+            ]
+        );
+    }
 }
@@ -1,7 +1,16 @@
-pub mod bmp; // TODO: feature
+#[cfg(feature = "bdf")]
+pub mod bdf;
+#[cfg(feature = "bmp")]
+pub mod bmp;
 pub mod filters;
-pub mod gif; // TODO: feature
+pub mod font;
+#[cfg(feature = "gif")]
+pub mod gif;
+#[cfg(feature = "png")]
+pub mod png;
+pub mod quantize;
 pub mod shapes;
+pub mod sprite_sheet;
 
 mod animation;
 mod color;
@@ -0,0 +1,388 @@
+use crate::{Animation, Color, Image, Pixel, Position, Region};
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    time::Duration,
+};
+
+#[derive(Debug)]
+pub struct PngImage {
+    image: Image,
+}
+
+impl PngImage {
+    pub fn new(image: Image) -> Self {
+        Self { image }
+    }
+
+    pub fn read_from<R: Read>(reader: R) -> Result<Self, png::DecodingError> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(
+            png::Transformations::EXPAND
+                | png::Transformations::STRIP_16
+                | png::Transformations::ALPHA,
+        );
+        let mut reader = decoder.read_info()?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf)?;
+        let (Ok(width), Ok(height)) = (i16::try_from(info.width), i16::try_from(info.height))
+        else {
+            return Err(png::DecodingError::LimitsExceeded);
+        };
+
+        let colors = buf[..info.buffer_size()]
+            .chunks_exact(info.color_type.samples())
+            .map(|c| match info.color_type {
+                png::ColorType::Grayscale => Color::rgb(c[0], c[0], c[0]),
+                png::ColorType::GrayscaleAlpha => Color::rgba(c[0], c[0], c[0], c[1]),
+                png::ColorType::Rgb => Color::rgb(c[0], c[1], c[2]),
+                png::ColorType::Rgba => Color::rgba(c[0], c[1], c[2], c[3]),
+                png::ColorType::Indexed => unreachable!("palette should have been expanded"),
+            });
+        let positions = (0..height).flat_map(|y| (0..width).map(move |x| Position::xy(x, y)));
+        let image = positions
+            .zip(colors)
+            .filter(|(_, color)| !color.is_transparent())
+            .map(|(position, color)| Pixel::new(position, color))
+            .collect();
+        Ok(Self { image })
+    }
+
+    pub fn into_image(self) -> Image {
+        self.image
+    }
+
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), png::EncodingError> {
+        let (size, colors) = self.image.to_size_and_colors();
+        let mut encoder = png::Encoder::new(writer, u32::from(size.width), u32::from(size.height));
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let data = if let Some(palette) = Palette::new(&colors) {
+            encoder.set_color(png::ColorType::Indexed);
+            encoder.set_palette(palette.to_plte());
+            if let Some(trns) = palette.to_trns() {
+                encoder.set_trns(trns);
+            }
+            colors.iter().map(|c| palette.index_of(*c)).collect()
+        } else {
+            encoder.set_color(png::ColorType::Rgba);
+            colors
+                .iter()
+                .flat_map(|c| [c.r, c.g, c.b, c.a].into_iter())
+                .collect::<Vec<_>>()
+        };
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&data)?;
+        writer.finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct AnimatedPngImage {
+    anime: Animation,
+    num_plays: u32,
+}
+
+impl AnimatedPngImage {
+    pub fn new(anime: Animation) -> Self {
+        Self {
+            anime,
+            num_plays: 1,
+        }
+    }
+
+    pub fn repeat(&mut self) -> &mut Self {
+        self.num_plays = 0;
+        self
+    }
+
+    /// Sets how many times the animation is played (`0` means infinitely).
+    pub fn num_plays(&mut self, n: u32) -> &mut Self {
+        self.num_plays = n;
+        self
+    }
+
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), png::EncodingError> {
+        let size = self.anime.get_max_frame_size();
+        let mut canvases = Vec::<(Vec<Color>, Duration)>::new();
+        for (n, frame) in self.anime.frames.iter().enumerate() {
+            // Frames may be smaller than the whole image, so they are placed on a canvas first.
+            let (frame_size, colors) = frame.to_size_and_colors();
+            let mut canvas = vec![Color::TRANSPARENT; size.area() as usize];
+            for (i, color) in colors.into_iter().enumerate() {
+                let x = i % usize::from(frame_size.width);
+                let y = i / usize::from(frame_size.width);
+                canvas[y * usize::from(size.width) + x] = color;
+            }
+
+            let duration = self.anime.get_nth_frame_duration(n);
+            match canvases.last_mut() {
+                Some((prev, prev_duration)) if *prev == canvas => *prev_duration += duration,
+                _ => canvases.push((canvas, duration)),
+            }
+        }
+
+        let mut encoder = png::Encoder::new(writer, u32::from(size.width), u32::from(size.height));
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_animated(canvases.len() as u32, self.num_plays)?;
+        let mut writer = encoder.write_header()?;
+        writer.set_blend_op(png::BlendOp::Source)?;
+        writer.set_dispose_op(png::DisposeOp::None)?;
+
+        let full_region = Region::new(Position::ORIGIN, size);
+        let mut prev = None;
+        for (canvas, duration) in &canvases {
+            // With `BlendOp::Source`, a frame replaces its region completely (including alpha),
+            // so only the pixels that differ from the previous frame need to be written.
+            let region = match prev {
+                None => full_region,
+                Some(prev) => full_region
+                    .positions()
+                    .zip(canvas.iter().zip(prev))
+                    .filter(|(_, (a, b))| a != b)
+                    .map(|(p, _)| p)
+                    .collect(),
+            };
+            prev = Some(canvas);
+
+            let (num, den) = to_frame_delay(*duration);
+            writer.set_frame_delay(num, den)?;
+            writer.reset_frame_position()?;
+            writer
+                .set_frame_dimension(u32::from(region.size.width), u32::from(region.size.height))?;
+            writer.set_frame_position(region.start.x as u32, region.start.y as u32)?;
+
+            let data = region
+                .positions()
+                .map(|p| canvas[p.y as usize * usize::from(size.width) + p.x as usize])
+                .flat_map(|c| [c.r, c.g, c.b, c.a].into_iter())
+                .collect::<Vec<_>>();
+            writer.write_image_data(&data)?;
+        }
+        writer.finish()
+    }
+}
+
+/// Returns `duration` in seconds as the closest fraction whose terms fit in `u16`.
+fn to_frame_delay(duration: Duration) -> (u16, u16) {
+    // The convergents of a continued fraction are its best approximations for their
+    // denominators, so e.g. `Duration::from_secs(1) / 60` becomes exactly 1/60.
+    let (mut num, mut den) = (duration.as_nanos(), 1_000_000_000);
+    let (mut prev, mut current) = ((0, 1), (1, 0));
+    let mut delay = (u16::MAX, 1);
+    while den != 0 {
+        let a = num / den;
+        let next = (a * current.0 + prev.0, a * current.1 + prev.1);
+        let (Ok(n), Ok(d)) = (u16::try_from(next.0), u16::try_from(next.1)) else {
+            break;
+        };
+        delay = (n, d);
+        (prev, current) = (current, next);
+        (num, den) = (den, num % den);
+    }
+    delay
+}
+
+#[derive(Debug)]
+struct Palette {
+    colors: Vec<Color>,
+    indices: BTreeMap<Color, u8>,
+}
+
+impl Palette {
+    const MAX_COLORS: usize = 256;
+
+    fn new(colors: &[Color]) -> Option<Self> {
+        let mut palette = Vec::new();
+        for &color in colors {
+            // Fully transparent pixels are all the same to a viewer.
+            let color = if color.is_transparent() {
+                Color::TRANSPARENT
+            } else {
+                color
+            };
+            if !palette.contains(&color) {
+                if palette.len() == Self::MAX_COLORS {
+                    return None;
+                }
+                palette.push(color);
+            }
+        }
+
+        // Non-opaque colors are placed first to keep the tRNS chunk as short as possible.
+        palette.sort_by_key(|c| c.a == 0xFF);
+        let indices = palette
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (c, i as u8))
+            .collect();
+        Some(Self {
+            colors: palette,
+            indices,
+        })
+    }
+
+    fn index_of(&self, color: Color) -> u8 {
+        let color = if color.is_transparent() {
+            Color::TRANSPARENT
+        } else {
+            color
+        };
+        self.indices[&color]
+    }
+
+    fn to_plte(&self) -> Vec<u8> {
+        self.colors
+            .iter()
+            .flat_map(|c| [c.r, c.g, c.b].into_iter())
+            .collect()
+    }
+
+    fn to_trns(&self) -> Option<Vec<u8>> {
+        let trns = self
+            .colors
+            .iter()
+            .take_while(|c| c.a != 0xFF)
+            .map(|c| c.a)
+            .collect::<Vec<_>>();
+        (!trns.is_empty()).then_some(trns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Size;
+
+    fn decode(bytes: &[u8]) -> (png::ColorType, Size, Vec<Color>) {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::ALPHA);
+        let mut reader = decoder.read_info().expect("invalid header");
+        let color_type = reader.info().color_type;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).expect("invalid frame");
+        let size = Size::new(info.width as u16, info.height as u16);
+        let colors = buf[..info.buffer_size()]
+            .chunks_exact(4)
+            .map(|c| Color::rgba(c[0], c[1], c[2], c[3]))
+            .collect();
+        (color_type, size, colors)
+    }
+
+    #[test]
+    fn indexed_round_trip_works() {
+        let image = Image::from_text(
+            [
+                ('#', Color::BLACK),
+                ('o', Color::RED.alpha(128)),
+                ('.', Color::WHITE),
+            ],
+            "#o.\n. #\n",
+        );
+
+        let mut bytes = Vec::new();
+        PngImage::new(image.clone())
+            .write_to(&mut bytes)
+            .expect("failed to encode");
+
+        let (color_type, size, colors) = decode(&bytes);
+        assert_eq!(color_type, png::ColorType::Indexed);
+        assert_eq!(image.to_size_and_colors(), (size, colors));
+    }
+
+    #[test]
+    fn rgba_round_trip_works() {
+        let image = (0..300)
+            .map(|i| {
+                let color = Color::rgba(i as u8, (i / 2) as u8, 7, 255 - (i % 3) as u8);
+                Pixel::new(Position::xy(i % 20, i / 20), color)
+            })
+            .collect::<Image>();
+
+        let mut bytes = Vec::new();
+        PngImage::new(image.clone())
+            .write_to(&mut bytes)
+            .expect("failed to encode");
+
+        let (color_type, size, colors) = decode(&bytes);
+        assert_eq!(color_type, png::ColorType::Rgba);
+        assert_eq!(image.to_size_and_colors(), (size, colors));
+    }
+
+    #[test]
+    fn read_from_works() {
+        let image = Image::from_text(
+            [('#', Color::BLACK), ('o', Color::RED.alpha(128))],
+            "#o \n  #\n",
+        );
+
+        let mut bytes = Vec::new();
+        PngImage::new(image.clone())
+            .write_to(&mut bytes)
+            .expect("failed to encode");
+
+        let decoded = PngImage::read_from(bytes.as_slice())
+            .expect("failed to decode")
+            .into_image();
+        assert_eq!(decoded.pixels.len(), 3);
+        assert_eq!(decoded.to_size_and_colors(), image.to_size_and_colors());
+
+        bytes.truncate(bytes.len() / 2);
+        assert!(PngImage::read_from(bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn animated_png_works() {
+        let palette = [('#', Color::BLACK), ('o', Color::RED.alpha(100))];
+        let frames = ["###\n#o#\n", "###\n#o#\n", "###\n# #\n", "o##\n# #\n"]
+            .map(|text| Image::from_text(palette, text));
+        let anime = Animation::new().frames(frames.clone()).fps10();
+
+        let mut bytes = Vec::new();
+        AnimatedPngImage::new(anime)
+            .repeat()
+            .write_to(&mut bytes)
+            .expect("failed to encode");
+
+        let mut reader = png::Decoder::new(bytes.as_slice())
+            .read_info()
+            .expect("invalid header");
+        let actl = reader.info().animation_control.expect("not animated");
+        assert_eq!((actl.num_frames, actl.num_plays), (3, 0));
+
+        let mut canvas = vec![Color::TRANSPARENT; 6];
+        let mut rects = Vec::new();
+        for expected in [&frames[0], &frames[2], &frames[3]] {
+            let mut buf = vec![0; reader.output_buffer_size()];
+            let info = reader.next_frame(&mut buf).expect("invalid frame");
+            let fctl = reader.info().frame_control.expect("no fcTL");
+            let region = Region::new(
+                Position::xy(fctl.x_offset as i16, fctl.y_offset as i16),
+                Size::new(fctl.width as u16, fctl.height as u16),
+            );
+            for (p, c) in region
+                .positions()
+                .zip(buf[..info.buffer_size()].chunks_exact(4))
+            {
+                canvas[p.y as usize * 3 + p.x as usize] = Color::rgba(c[0], c[1], c[2], c[3]);
+            }
+            assert_eq!(canvas, expected.to_size_and_colors().1);
+            rects.push((region, fctl.delay_num, fctl.delay_den));
+        }
+        assert_eq!(
+            rects,
+            [
+                (Region::new(Position::ORIGIN, Size::new(3, 2)), 1, 5),
+                (Region::new(Position::xy(1, 1), Size::new(1, 1)), 1, 10),
+                (Region::new(Position::ORIGIN, Size::new(1, 1)), 1, 10),
+            ]
+        );
+
+        assert_eq!(to_frame_delay(Duration::from_secs(1) / 60), (1, 60));
+        assert_eq!(to_frame_delay(Duration::from_millis(1234)), (617, 500));
+        assert_eq!(to_frame_delay(Duration::ZERO), (0, 1));
+        assert_eq!(to_frame_delay(Duration::from_secs(100_000)), (u16::MAX, 1));
+    }
+}
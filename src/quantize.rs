@@ -0,0 +1,353 @@
+use crate::{filters::Filter, Animation, Color, Image};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QuantizeMethod {
+    /// Repeatedly splits the most spread out group of colors at its median.
+    #[default]
+    MedianCut,
+
+    /// Refines the median cut palette with k-means clustering.
+    KMeans,
+}
+
+/// Reduces the colors of images to a generated palette.
+///
+/// Colors are compared in the Oklab color space (plus alpha), so that the palette allocates more
+/// entries to differences that are easier to see. Transparent pixels are left as they are and
+/// are not included in the palette.
+#[derive(Debug, Clone, Copy)]
+pub struct Quantizer {
+    max_colors: usize,
+    method: QuantizeMethod,
+}
+
+impl Quantizer {
+    const MAX_KMEANS_ITERATIONS: usize = 16;
+
+    pub const fn new(max_colors: usize) -> Self {
+        Self {
+            max_colors,
+            method: QuantizeMethod::MedianCut,
+        }
+    }
+
+    pub const fn method(self, method: QuantizeMethod) -> Self {
+        Self { method, ..self }
+    }
+
+    /// Returns a palette of at most `max_colors` colors representing `colors`.
+    pub fn palette(&self, colors: impl IntoIterator<Item = Color>) -> Vec<Color> {
+        let histogram = histogram(colors.into_iter().filter(|c| !c.is_transparent()));
+        let palette = median_cut_with(histogram.clone(), self.max_colors, to_oklab, from_oklab);
+        match self.method {
+            QuantizeMethod::MedianCut => palette,
+            QuantizeMethod::KMeans => kmeans(&histogram, palette, Self::MAX_KMEANS_ITERATIONS),
+        }
+    }
+
+    /// Returns the palette for the pixels of `image` (including its children) and the image
+    /// with each pixel replaced by the nearest palette color.
+    pub fn quantize_image(&self, image: &Image) -> (Vec<Color>, Image) {
+        let palette = self.palette(image.iter().map(|p| p.color));
+        let image = image.clone().filter(Remap(&palette));
+        (palette, image)
+    }
+
+    /// Like [`Quantizer::quantize_image()`], but shares one palette across all frames.
+    pub fn quantize_animation(&self, anime: &Animation) -> (Vec<Color>, Animation) {
+        let colors = anime.frames.iter().flat_map(|f| f.iter().map(|p| p.color));
+        let palette = self.palette(colors);
+        let anime = anime.clone().filter(Remap(&palette));
+        (palette, anime)
+    }
+}
+
+struct Remap<'a>(&'a [Color]);
+
+impl Filter for Remap<'_> {
+    fn filter(&self, image: &mut Image) {
+        let palette = self.0.iter().map(|&c| to_oklab(c)).collect::<Vec<_>>();
+        for color in image.pixels.values_mut() {
+            if color.is_transparent() {
+                continue;
+            }
+            if let Some(i) = nearest_point(&palette, to_oklab(*color)) {
+                *color = self.0[i];
+            }
+        }
+        for child in &mut image.children {
+            self.filter(child);
+        }
+    }
+}
+
+/// Returns the index of the color in `palette` closest to `color`.
+pub(crate) fn nearest_index(palette: &[Color], color: Color) -> Option<usize> {
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, c)| distance(**c, color))
+        .map(|(i, _)| i)
+}
+
+pub(crate) fn distance(a: Color, b: Color) -> u32 {
+    fn d(a: u8, b: u8) -> u32 {
+        let d = u32::from(a.abs_diff(b));
+        d * d
+    }
+    d(a.r, b.r) + d(a.g, b.g) + d(a.b, b.b) + d(a.a, b.a)
+}
+
+pub(crate) type Point = [f64; 4];
+
+pub(crate) fn histogram(colors: impl IntoIterator<Item = Color>) -> BTreeMap<Color, u64> {
+    let mut histogram = BTreeMap::<Color, u64>::new();
+    for color in colors {
+        *histogram.entry(color).or_default() += 1;
+    }
+    histogram
+}
+
+/// Reduces the colors of `histogram` to at most `max_colors` colors using the median cut
+/// algorithm in the color space given by `to_point` and `from_point`.
+///
+/// The result is deterministic: the same input always yields the same palette.
+pub(crate) fn median_cut_with(
+    histogram: BTreeMap<Color, u64>,
+    max_colors: usize,
+    to_point: fn(Color) -> Point,
+    from_point: fn(Point) -> Color,
+) -> Vec<Color> {
+    if histogram.is_empty() || max_colors == 0 {
+        return Vec::new();
+    }
+
+    let entries = histogram
+        .into_iter()
+        .map(|(color, n)| (color, n, to_point(color)))
+        .collect();
+    let mut boxes = vec![ColorBox::new(entries)];
+    while boxes.len() < max_colors {
+        let Some((i, _)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.entries.len() > 1)
+            .map(|(i, b)| (i, b.priority()))
+            .max_by(|(i, a), (j, b)| a.total_cmp(b).then(j.cmp(i)))
+        else {
+            break;
+        };
+        let (left, right) = boxes.swap_remove(i).split();
+        boxes.push(left);
+        boxes.push(right);
+    }
+    boxes.iter().map(|b| from_point(b.average())).collect()
+}
+
+fn kmeans(histogram: &BTreeMap<Color, u64>, palette: Vec<Color>, iterations: usize) -> Vec<Color> {
+    let mut centroids = palette.into_iter().map(to_oklab).collect::<Vec<_>>();
+    for _ in 0..iterations {
+        let mut sums = vec![([0.0; 4], 0u64); centroids.len()];
+        for (&color, &n) in histogram {
+            let point = to_oklab(color);
+            let Some(i) = nearest_point(&centroids, point) else {
+                break;
+            };
+            for (s, v) in sums[i].0.iter_mut().zip(point) {
+                *s += v * n as f64;
+            }
+            sums[i].1 += n;
+        }
+
+        let next = sums
+            .iter()
+            .zip(&centroids)
+            .map(|((sum, n), &c)| {
+                if *n == 0 {
+                    c
+                } else {
+                    sum.map(|s| s / *n as f64)
+                }
+            })
+            .collect::<Vec<_>>();
+        if next == centroids {
+            break;
+        }
+        centroids = next;
+    }
+
+    let mut palette = Vec::<Color>::new();
+    for color in centroids.into_iter().map(from_oklab) {
+        if !palette.contains(&color) {
+            palette.push(color);
+        }
+    }
+    palette
+}
+
+fn nearest_point(points: &[Point], point: Point) -> Option<usize> {
+    let distance = |p: &Point| {
+        p.iter()
+            .zip(point)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f64>()
+    };
+    points
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+        .map(|(i, _)| i)
+}
+
+fn to_oklab(color: Color) -> Point {
+    let linear = |v: u8| {
+        let v = f64::from(v) / 255.0;
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(color.r), linear(color.g), linear(color.b));
+    let l = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+    let m = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+    let s = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+    [
+        0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s,
+        1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s,
+        0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s,
+        f64::from(color.a) / 255.0,
+    ]
+}
+
+fn from_oklab([lightness, a, b, alpha]: Point) -> Color {
+    let l = (lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+    let m = (lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+    let s = (lightness - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+    let srgb = |v: f64| {
+        let v = if v <= 0.003_130_8 {
+            v * 12.92
+        } else {
+            1.055 * v.powf(1.0 / 2.4) - 0.055
+        };
+        (v * 255.0).round().clamp(0.0, 255.0) as u8
+    };
+    Color::rgba(
+        srgb(4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s),
+        srgb(-1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s),
+        srgb(-0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s),
+        (alpha * 255.0).round().clamp(0.0, 255.0) as u8,
+    )
+}
+
+#[derive(Debug)]
+struct ColorBox {
+    entries: Vec<(Color, u64, Point)>,
+}
+
+impl ColorBox {
+    fn new(entries: Vec<(Color, u64, Point)>) -> Self {
+        Self { entries }
+    }
+
+    fn ranges(&self) -> Point {
+        let mut ranges = [0.0; 4];
+        for (i, range) in ranges.iter_mut().enumerate() {
+            let values = self.entries.iter().map(|(_, _, p)| p[i]);
+            let min = values.clone().fold(f64::INFINITY, f64::min);
+            let max = values.fold(f64::NEG_INFINITY, f64::max);
+            *range = max - min;
+        }
+        ranges
+    }
+
+    fn priority(&self) -> f64 {
+        let population = self.entries.iter().map(|(_, n, _)| n).sum::<u64>();
+        let range = self.ranges().into_iter().fold(0.0, f64::max);
+        range * population as f64
+    }
+
+    fn split(mut self) -> (Self, Self) {
+        let ranges = self.ranges();
+        let channel = (0..4)
+            .max_by(|&i, &j| ranges[i].total_cmp(&ranges[j]).then(j.cmp(&i)))
+            .unwrap_or(0);
+        self.entries
+            .sort_by(|a, b| a.2[channel].total_cmp(&b.2[channel]).then(a.0.cmp(&b.0)));
+
+        let population = self.entries.iter().map(|(_, n, _)| n).sum::<u64>();
+        let mut acc = 0;
+        let mut mid = 1;
+        for (i, (_, n, _)) in self.entries.iter().enumerate() {
+            acc += n;
+            if acc * 2 >= population {
+                mid = i + 1;
+                break;
+            }
+        }
+        let mid = mid.clamp(1, self.entries.len() - 1);
+        let right = self.entries.split_off(mid);
+        (self, Self::new(right))
+    }
+
+    fn average(&self) -> Point {
+        let mut sum = [0.0; 4];
+        let mut population = 0;
+        for (_, n, point) in &self.entries {
+            for (s, v) in sum.iter_mut().zip(point) {
+                *s += v * *n as f64;
+            }
+            population += n;
+        }
+        sum.map(|s| s / population.max(1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pixel, Position};
+
+    #[test]
+    fn quantize_works() {
+        let reds = (0..8).map(|i| Color::rgb(200 + i, 0, 0));
+        let blues = (0..8).map(|i| Color::rgb(0, 0, 200 + i));
+        let image = reds
+            .chain(blues)
+            .chain([Color::TRANSPARENT])
+            .enumerate()
+            .map(|(x, c)| Pixel::new(Position::xy(x as i16, 0), c))
+            .collect::<Image>();
+
+        for method in [QuantizeMethod::MedianCut, QuantizeMethod::KMeans] {
+            let (palette, quantized) = Quantizer::new(2).method(method).quantize_image(&image);
+            assert_eq!(palette.len(), 2, "{method:?}");
+            assert!(palette.iter().any(|c| c.r > 190 && c.b == 0));
+            assert!(palette.iter().any(|c| c.b > 190 && c.r == 0));
+            assert_eq!(quantized.get_color(Position::xy(16, 0)), Color::TRANSPARENT);
+            assert!(quantized
+                .pixels
+                .values()
+                .all(|c| c.is_transparent() || palette.contains(c)));
+            assert_ne!(
+                quantized.get_color(Position::xy(0, 0)),
+                quantized.get_color(Position::xy(8, 0))
+            );
+        }
+
+        let anime = Animation::new().frames([image.clone(), image.filter(crate::filters::Erase)]);
+        let (palette, anime) = Quantizer::new(1).quantize_animation(&anime);
+        assert_eq!(palette.len(), 1);
+        assert_eq!(anime.frames[0].get_color(Position::xy(0, 0)), palette[0]);
+
+        for color in [
+            Color::RED,
+            Color::TEAL,
+            Color::WHITE,
+            Color::BLACK.alpha(10),
+        ] {
+            assert_eq!(from_oklab(to_oklab(color)), color);
+        }
+    }
+}
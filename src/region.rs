@@ -47,6 +47,12 @@ impl Region {
         Position::xy(self.right(), self.bottom())
     }
 
+    pub fn contains(self, position: Position) -> bool {
+        !self.is_empty()
+            && (self.left()..=self.right()).contains(&position.x)
+            && (self.top()..=self.bottom()).contains(&position.y)
+    }
+
     pub fn positions(self) -> impl Iterator<Item = Position> {
         self.size
             .positions()
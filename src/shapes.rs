@@ -1,5 +1,24 @@
+mod arc;
+mod bezier;
+mod circle;
+mod ellipse;
 mod line;
+mod nine_slice;
+mod path;
+mod polygon;
+mod polyline;
 mod rectangle;
+mod stroke;
+mod text;
 
+pub use self::arc::Arc;
+pub use self::bezier::Bezier;
+pub use self::circle::Circle;
+pub use self::ellipse::Ellipse;
 pub use self::line::Line;
+pub use self::nine_slice::NineSlice;
+pub use self::polygon::{FillRule, Polygon};
+pub use self::polyline::Polyline;
 pub use self::rectangle::Rectangle;
+pub use self::stroke::Stroke;
+pub use self::text::{Text, TextAlign};
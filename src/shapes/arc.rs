@@ -0,0 +1,119 @@
+use crate::{
+    shapes::{path, Stroke},
+    Color, Image, Pixel, Position,
+};
+
+/// Circular arc around `position`.
+///
+/// Angles are in degrees, measured clockwise (on screen) from the positive x-axis.
+#[derive(Debug, Clone, Copy)]
+pub struct Arc {
+    pub color: Color,
+    pub position: Position,
+    pub radius: u16,
+    pub start_angle: f32,
+    pub end_angle: f32,
+    pub stroke: Stroke,
+}
+
+impl Arc {
+    pub const fn new() -> Self {
+        Self {
+            color: Color::BLACK,
+            position: Position::ORIGIN,
+            radius: 0,
+            start_angle: 0.0,
+            end_angle: 360.0,
+            stroke: Stroke::new(),
+        }
+    }
+
+    pub const fn color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    pub const fn position(self, position: Position) -> Self {
+        Self { position, ..self }
+    }
+
+    pub const fn radius(self, radius: u16) -> Self {
+        Self { radius, ..self }
+    }
+
+    pub const fn angles(self, start_angle: f32, end_angle: f32) -> Self {
+        Self {
+            start_angle,
+            end_angle,
+            ..self
+        }
+    }
+
+    pub const fn stroke(self, stroke: Stroke) -> Self {
+        Self { stroke, ..self }
+    }
+
+    pub fn to_image(self) -> Image {
+        self.into_iter().collect()
+    }
+}
+
+impl Default for Arc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoIterator for Arc {
+    type Item = Pixel;
+    type IntoIter = Box<dyn Iterator<Item = Pixel>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let r = f64::from(self.radius);
+        let start = f64::from(self.start_angle).to_radians();
+        let sweep = f64::from(self.end_angle - self.start_angle).to_radians();
+        let steps = (sweep.abs() * r * 2.0).ceil().max(1.0) as usize;
+        let center = (f64::from(self.position.x), f64::from(self.position.y));
+
+        let positions = path::trace((0..=steps).map(|i| {
+            let theta = start + sweep * i as f64 / steps as f64;
+            (center.0 + r * theta.cos(), center.1 + r * theta.sin())
+        }));
+        Box::new(self.stroke.to_pixels(positions, self.color).into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xy;
+
+    #[test]
+    fn arc_works() {
+        let expected = |text: &str| {
+            let mut image = Image::from_text([('#', Color::BLACK)], text);
+            image.pixels.retain(|_, c| !c.is_transparent());
+            image
+        };
+
+        let quarter = Arc::new().radius(5).angles(0.0, 90.0);
+        assert_eq!(
+            quarter.to_image(),
+            expected(".....#\n.....#\n.....#\n....#.\n...#..\n###...\n")
+        );
+        let path = quarter.into_iter().map(|p| p.position).collect::<Vec<_>>();
+        assert_eq!(path.first(), Some(&xy(5, 0)));
+        assert_eq!(path.last(), Some(&xy(0, 5)));
+
+        let reversed = Arc::new().radius(5).angles(90.0, 0.0);
+        let path = reversed.into_iter().map(|p| p.position).collect::<Vec<_>>();
+        assert_eq!(path.first(), Some(&xy(0, 5)));
+        assert_eq!(path.last(), Some(&xy(5, 0)));
+
+        let upper_half = Arc::new()
+            .position(xy(3, 3))
+            .radius(3)
+            .angles(180.0, 360.0)
+            .to_image();
+        assert_eq!(upper_half, expected("..###..\n.#...#.\n#.....#\n#.....#\n"));
+    }
+}
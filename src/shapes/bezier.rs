@@ -0,0 +1,105 @@
+use crate::{
+    shapes::{path, Stroke},
+    Color, Image, Pixel, Position,
+};
+
+#[derive(Debug, Clone)]
+pub struct Bezier {
+    pub color: Color,
+    pub points: Vec<Position>,
+    pub stroke: Stroke,
+}
+
+impl Bezier {
+    pub fn quadratic(start: Position, control: Position, end: Position) -> Self {
+        Self {
+            color: Color::BLACK,
+            points: vec![start, control, end],
+            stroke: Stroke::new(),
+        }
+    }
+
+    pub fn cubic(start: Position, control0: Position, control1: Position, end: Position) -> Self {
+        Self {
+            color: Color::BLACK,
+            points: vec![start, control0, control1, end],
+            stroke: Stroke::new(),
+        }
+    }
+
+    pub fn color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn stroke(self, stroke: Stroke) -> Self {
+        Self { stroke, ..self }
+    }
+
+    pub fn to_image(self) -> Image {
+        self.into_iter().collect()
+    }
+
+    fn point_at(&self, t: f64) -> (f64, f64) {
+        // De Casteljau's algorithm.
+        let mut points = self
+            .points
+            .iter()
+            .map(|p| (f64::from(p.x), f64::from(p.y)))
+            .collect::<Vec<_>>();
+        while points.len() > 1 {
+            points = points
+                .windows(2)
+                .map(|w| {
+                    (
+                        w[0].0 + (w[1].0 - w[0].0) * t,
+                        w[0].1 + (w[1].1 - w[0].1) * t,
+                    )
+                })
+                .collect();
+        }
+        points.first().copied().unwrap_or_default()
+    }
+}
+
+impl IntoIterator for Bezier {
+    type Item = Pixel;
+    type IntoIter = Box<dyn Iterator<Item = Pixel>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // The speed of a Bézier curve never exceeds `degree * (the longest control polygon edge)`,
+        // so these steps are at most half a pixel apart.
+        let degree = self.points.len().saturating_sub(1) as f64;
+        let max_edge = self
+            .points
+            .windows(2)
+            .map(|w| f64::from((w[1].x - w[0].x).abs().max((w[1].y - w[0].y).abs())))
+            .fold(0.0, f64::max);
+        let steps = (degree * max_edge * 2.0).ceil().max(1.0) as usize;
+
+        let positions = path::trace((0..=steps).map(|i| self.point_at(i as f64 / steps as f64)));
+        Box::new(self.stroke.to_pixels(positions, self.color).into_iter())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xy;
+
+    #[test]
+    fn bezier_works() {
+        let expected = |text: &str| {
+            let mut image = Image::from_text([('#', Color::BLACK)], text);
+            image.pixels.retain(|_, c| !c.is_transparent());
+            image
+        };
+
+        let quadratic = Bezier::quadratic(xy(0, 0), xy(4, 0), xy(4, 4)).to_image();
+        assert_eq!(quadratic, expected("###..\n...#.\n....#\n....#\n....#\n"));
+
+        let cubic = Bezier::cubic(xy(0, 4), xy(0, 0), xy(6, 0), xy(6, 4)).to_image();
+        assert_eq!(
+            cubic,
+            expected(".......\n..###..\n.#...#.\n#.....#\n#.....#\n")
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    shapes::{Ellipse, Stroke},
+    Color, Image, Pixel, Position, Size,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Circle {
+    pub color: Color,
+    pub position: Position,
+    pub diameter: u16,
+    pub fill: bool,
+    pub stroke: Stroke,
+}
+
+impl Circle {
+    pub const fn new() -> Self {
+        Self {
+            color: Color::BLACK,
+            position: Position::ORIGIN,
+            diameter: 0,
+            fill: false,
+            stroke: Stroke::new(),
+        }
+    }
+
+    pub const fn color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    pub const fn position(self, position: Position) -> Self {
+        Self { position, ..self }
+    }
+
+    pub const fn diameter(self, diameter: u16) -> Self {
+        Self { diameter, ..self }
+    }
+
+    pub const fn fill(self) -> Self {
+        Self { fill: true, ..self }
+    }
+
+    pub const fn stroke(self, stroke: Stroke) -> Self {
+        Self { stroke, ..self }
+    }
+
+    pub fn to_image(self) -> Image {
+        self.into_iter().collect()
+    }
+
+    const fn to_ellipse(self) -> Ellipse {
+        Ellipse {
+            color: self.color,
+            position: self.position,
+            size: Size::square(self.diameter),
+            fill: self.fill,
+            stroke: self.stroke,
+        }
+    }
+}
+
+impl Default for Circle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoIterator for Circle {
+    type Item = Pixel;
+    type IntoIter = Box<dyn Iterator<Item = Pixel>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.to_ellipse().into_iter()
+    }
+}
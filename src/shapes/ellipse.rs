@@ -0,0 +1,153 @@
+use crate::{shapes::Stroke, Color, Image, Pixel, Position, Size};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ellipse {
+    pub color: Color,
+    pub position: Position,
+    pub size: Size,
+    pub fill: bool,
+    pub stroke: Stroke,
+}
+
+impl Ellipse {
+    pub const fn new() -> Self {
+        Self {
+            color: Color::BLACK,
+            position: Position::ORIGIN,
+            size: Size::EMPTY,
+            fill: false,
+            stroke: Stroke::new(),
+        }
+    }
+
+    pub const fn color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    pub const fn position(self, position: Position) -> Self {
+        Self { position, ..self }
+    }
+
+    pub const fn size(self, size: Size) -> Self {
+        Self { size, ..self }
+    }
+
+    pub const fn fill(self) -> Self {
+        Self { fill: true, ..self }
+    }
+
+    pub const fn stroke(self, stroke: Stroke) -> Self {
+        Self { stroke, ..self }
+    }
+
+    pub fn to_image(self) -> Image {
+        self.into_iter().collect()
+    }
+}
+
+impl Default for Ellipse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoIterator for Ellipse {
+    type Item = Pixel;
+    type IntoIter = Box<dyn Iterator<Item = Pixel>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let inside = inside_positions(self.size);
+        if self.fill {
+            return Box::new(
+                inside
+                    .into_iter()
+                    .map(move |p| Pixel::new(self.position + p, self.color)),
+            );
+        }
+
+        // The outline is ordered by angle, so that dashes and color sequences of the stroke follow it.
+        let center_x = f64::from(self.size.width.saturating_sub(1)) / 2.0;
+        let center_y = f64::from(self.size.height.saturating_sub(1)) / 2.0;
+        let angle = |p: &Position| (f64::from(p.y) - center_y).atan2(f64::from(p.x) - center_x);
+        let mut path = outline_positions(&inside).into_iter().collect::<Vec<_>>();
+        path.sort_by(|a, b| angle(a).total_cmp(&angle(b)).then(a.cmp(b)));
+        let path = path.into_iter().map(move |p| self.position + p);
+        Box::new(self.stroke.to_pixels(path, self.color).into_iter())
+    }
+}
+
+// Evaluates the ellipse equation at the midpoint of each pixel.
+// Coordinates are doubled so that both odd and even sizes can be handled with integers.
+fn inside_positions(size: Size) -> BTreeSet<Position> {
+    let w = i128::from(size.width);
+    let h = i128::from(size.height);
+    if w <= 2 || h <= 2 {
+        return size.positions().collect();
+    }
+
+    size.positions()
+        .filter(|p| {
+            let dx = 2 * i128::from(p.x) + 1 - w;
+            let dy = 2 * i128::from(p.y) + 1 - h;
+            dx * dx * h * h + dy * dy * w * w <= w * w * h * h - 2 * w * h
+        })
+        .collect()
+}
+
+fn outline_positions(inside: &BTreeSet<Position>) -> BTreeSet<Position> {
+    let mut outline = inside
+        .iter()
+        .copied()
+        .filter(|p| neighbors4(*p).any(|n| !inside.contains(&n)))
+        .collect::<BTreeSet<_>>();
+    remove_l_corners(&mut outline);
+    outline
+}
+
+pub(crate) fn neighbors4(p: Position) -> impl Iterator<Item = Position> {
+    [p.move_y(-1), p.move_x(-1), p.move_x(1), p.move_y(1)].into_iter()
+}
+
+/// Removes pixels that only connect a horizontal and a vertical neighbor,
+/// since those neighbors are already connected diagonally.
+pub(crate) fn remove_l_corners(positions: &mut BTreeSet<Position>) {
+    let candidates = positions.iter().copied().collect::<Vec<_>>();
+    for p in candidates {
+        let neighbors = Size::square(3)
+            .positions()
+            .map(|o| p + o - Position::xy(1, 1))
+            .filter(|n| *n != p && positions.contains(n))
+            .collect::<Vec<_>>();
+        if let [a, b] = neighbors[..] {
+            if (a.x - b.x).abs() == 1 && (a.y - b.y).abs() == 1 {
+                positions.remove(&p);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outline_works() {
+        let ellipse = Ellipse::new().color(Color::RED);
+        let text = |size| ellipse.size(size).to_image().to_text();
+        assert_eq!(text(Size::square(4)), " 00 \n0  0\n0  0\n 00 \n");
+        assert_eq!(
+            text(Size::square(7)),
+            "  000  \n 0   0 \n0     0\n0     0\n0     0\n 0   0 \n  000  \n"
+        );
+        assert_eq!(text(Size::new(7, 3)), " 00000 \n0     0\n 00000 \n");
+    }
+
+    #[test]
+    fn fill_works() {
+        let ellipse = Ellipse::new().color(Color::RED).fill();
+        let text = |size| ellipse.size(size).to_image().to_text();
+        assert_eq!(text(Size::square(3)), " 0 \n000\n 0 \n");
+        assert_eq!(text(Size::new(6, 4)), " 0000 \n000000\n000000\n 0000 \n");
+    }
+}
@@ -1,10 +1,11 @@
-use crate::{Color, Image, Pixel, Position};
+use crate::{shapes::Stroke, Color, Image, Pixel, Position};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Line {
     pub color: Color,
     pub position: Position,
     pub vector: Position,
+    pub stroke: Stroke,
 }
 
 impl Line {
@@ -13,6 +14,7 @@ impl Line {
             color: Color::BLACK,
             position: Position::ORIGIN,
             vector: Position::ORIGIN,
+            stroke: Stroke::new(),
         }
     }
 
@@ -28,6 +30,10 @@ impl Line {
         Self { vector, ..self }
     }
 
+    pub const fn stroke(self, stroke: Stroke) -> Self {
+        Self { stroke, ..self }
+    }
+
     pub fn to_image(self) -> Image {
         self.into_iter().collect()
     }
@@ -59,12 +65,13 @@ impl IntoIterator for Line {
             let r = Rational::new(dy, dx);
             (f, r, dy, p0.y, sign_y, p0.x, sign_x)
         };
-        Box::new((0..n).map(move |i| {
+        let path = (0..n).map(move |i| {
             if i != 0 && (i - 1) / r != i / r {
                 v1 += sign1;
             }
-            Pixel::new(f(v0 + i * sign0, v1) + self.position, self.color)
-        }))
+            f(v0 + i * sign0, v1) + self.position
+        });
+        Box::new(self.stroke.to_pixels(path, self.color).into_iter())
     }
 }
 
@@ -0,0 +1,151 @@
+use crate::{Image, Pixel, Position, Size};
+
+/// Image scaled by keeping its corners as they are and stretching (or tiling) its edges and center.
+#[derive(Debug, Clone)]
+pub struct NineSlice {
+    pub source: Image,
+    pub position: Position,
+    pub size: Size,
+    pub left: u16,
+    pub top: u16,
+    pub right: u16,
+    pub bottom: u16,
+    pub tile: bool,
+}
+
+impl NineSlice {
+    pub fn new(source: Image) -> Self {
+        Self {
+            source,
+            position: Position::ORIGIN,
+            size: Size::EMPTY,
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+            tile: false,
+        }
+    }
+
+    pub fn position(self, position: Position) -> Self {
+        Self { position, ..self }
+    }
+
+    pub fn size(self, size: Size) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Sets the widths of the borders of `source` that are not stretched.
+    pub fn insets(self, left: u16, top: u16, right: u16, bottom: u16) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+            ..self
+        }
+    }
+
+    /// Repeats the edges and center instead of stretching them.
+    pub fn tile(self) -> Self {
+        Self { tile: true, ..self }
+    }
+
+    pub fn to_image(self) -> Image {
+        self.into_iter().collect()
+    }
+
+    fn source_coordinate(
+        &self,
+        v: u16,
+        src_len: u16,
+        dst_len: u16,
+        start: u16,
+        end: u16,
+    ) -> Option<u16> {
+        if v < start {
+            return Some(v);
+        }
+        if v >= dst_len.saturating_sub(end) {
+            return (src_len + v).checked_sub(dst_len);
+        }
+
+        let src_middle = src_len.checked_sub(start + end).filter(|&n| n > 0)?;
+        let dst_middle = u32::from(dst_len - start - end);
+        let offset = u32::from(v - start);
+        let offset = if self.tile {
+            offset % u32::from(src_middle)
+        } else {
+            offset * u32::from(src_middle) / dst_middle
+        };
+        Some(start + offset as u16)
+    }
+}
+
+impl IntoIterator for NineSlice {
+    type Item = Pixel;
+    type IntoIter = Box<dyn Iterator<Item = Pixel>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Like sprites, the source is measured from the origin.
+        let region = self.source.get_region();
+        let src_size = if region.is_empty() {
+            Size::EMPTY
+        } else {
+            let max = region.bottom_right();
+            Size::new((max.x + 1).max(0) as u16, (max.y + 1).max(0) as u16)
+        };
+
+        let pixels = self
+            .size
+            .positions()
+            .filter_map(|p| {
+                let (x, y) = (p.x as u16, p.y as u16);
+                let src_x = self.source_coordinate(
+                    x,
+                    src_size.width,
+                    self.size.width,
+                    self.left,
+                    self.right,
+                )?;
+                let src_y = self.source_coordinate(
+                    y,
+                    src_size.height,
+                    self.size.height,
+                    self.top,
+                    self.bottom,
+                )?;
+                let color = self
+                    .source
+                    .get_color(Position::xy(src_x as i16, src_y as i16));
+                (!color.is_transparent()).then(|| Pixel::new(self.position + p, color))
+            })
+            .collect::<Vec<_>>();
+        Box::new(pixels.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn nine_slice_works() {
+        let palette = [('#', Color::BLACK), ('o', Color::RED), ('x', Color::BLUE)];
+        let source = Image::from_text(palette, "#o#\nox.\n#o#\n");
+        let slice = NineSlice::new(source).insets(1, 1, 1, 1);
+
+        let stretched = slice.clone().size(Size::new(5, 4)).to_image();
+        let expected = Image::from_text(palette, "#ooo#\noxxx.\noxxx.\n#ooo#\n");
+        assert_eq!(stretched.to_text(), expected.to_text());
+
+        let tiled = slice
+            .insets(1, 0, 0, 0)
+            .tile()
+            .size(Size::new(6, 3))
+            .to_image();
+        let expected = Image::from_text(palette, "#o#o#o\nox.x.x\n#o#o#o\n");
+        assert_eq!(tiled.to_text(), expected.to_text());
+    }
+}
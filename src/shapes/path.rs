@@ -0,0 +1,139 @@
+use crate::Position;
+use std::collections::BTreeSet;
+
+/// Converts sampled points on a curve into a single-pixel-wide, 8-connected path.
+///
+/// Consecutive samples must be close enough (less than a pixel apart) for the result to be connected.
+pub(crate) fn trace(samples: impl IntoIterator<Item = (f64, f64)>) -> Vec<Position> {
+    // Samples are subdivided so that the closest one to each pixel center approximates the
+    // distance between the pixel and the curve.
+    const SUBDIVISIONS: u32 = 8;
+
+    let mut path = Vec::<(Position, f64)>::new();
+    let mut prev = None::<(f64, f64)>;
+    for (x, y) in samples {
+        let (x0, y0) = prev.unwrap_or((x, y));
+        prev = Some((x, y));
+        for i in 1..=SUBDIVISIONS {
+            let t = f64::from(i) / f64::from(SUBDIVISIONS);
+            let (x, y) = (x0 + (x - x0) * t, y0 + (y - y0) * t);
+            let p = Position::xy(x.round() as i16, y.round() as i16);
+            let distance = (x - f64::from(p.x)).hypot(y - f64::from(p.y));
+            match path.last_mut() {
+                Some((last, d)) if *last == p => *d = d.min(distance),
+                _ => path.push((p, distance)),
+            }
+        }
+    }
+
+    // Closed or self-intersecting curves visit some pixels more than once.
+    let mut visited = BTreeSet::new();
+    pixel_perfect(path)
+        .into_iter()
+        .filter(|p| visited.insert(*p))
+        .collect()
+}
+
+/// Removes "L-corner" pixels, i.e., pixels between a horizontal and a vertical step,
+/// which make strokes look doubled.
+///
+/// Each pixel comes with its distance from the curve. Where two corners overlap, only the one
+/// farther from the curve is removed, so that the result does not depend on the direction of the path.
+pub(crate) fn pixel_perfect(path: Vec<(Position, f64)>) -> Vec<Position> {
+    let is_l_corner = |prev: Position, p: Position, next: Position| {
+        (prev.x == p.x || prev.y == p.y)
+            && (next.x == p.x || next.y == p.y)
+            && prev.x != next.x
+            && prev.y != next.y
+    };
+
+    let mut result = Vec::<Position>::with_capacity(path.len());
+    for (i, &(p, distance)) in path.iter().enumerate() {
+        if let (Some(&prev), Some(&(next, next_distance))) = (result.last(), path.get(i + 1)) {
+            if is_l_corner(prev, p, next) {
+                let next_is_l_corner = path
+                    .get(i + 2)
+                    .is_some_and(|&(after, _)| is_l_corner(p, next, after));
+                if !(next_is_l_corner && distance < next_distance) {
+                    continue;
+                }
+            }
+        }
+        result.push(p);
+    }
+    result
+}
+
+/// Orders the positions of a closed, single-pixel-wide outline by walking along it clockwise.
+pub(crate) fn order_loop(mut positions: BTreeSet<Position>) -> Vec<Position> {
+    // Orthogonal neighbors are preferred so that no pixel is skipped by a diagonal step.
+    const DIRECTIONS: [(i16, i16); 8] = [
+        (1, 0),
+        (0, 1),
+        (-1, 0),
+        (0, -1),
+        (1, 1),
+        (-1, 1),
+        (-1, -1),
+        (1, -1),
+    ];
+
+    let Some(mut current) = positions.pop_first() else {
+        return Vec::new();
+    };
+    let mut path = vec![current];
+    while let Some(next) = DIRECTIONS
+        .iter()
+        .map(|&(x, y)| current.move_xy(x, y))
+        .find(|p| positions.contains(p))
+    {
+        positions.remove(&next);
+        path.push(next);
+        current = next;
+    }
+    path.extend(positions);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xy;
+
+    #[test]
+    fn pixel_perfect_works() {
+        let path = [xy(0, 0), xy(1, 0), xy(1, 1), xy(2, 1), xy(3, 1), xy(3, 2)];
+        assert_eq!(
+            pixel_perfect(path.map(|p| (p, 0.0)).to_vec()),
+            [xy(0, 0), xy(1, 1), xy(2, 1), xy(3, 2)]
+        );
+
+        // Of two overlapping corners, the one closer to the curve is kept.
+        let path = [
+            (xy(0, 0), 0.0),
+            (xy(0, 1), 0.2),
+            (xy(1, 1), 0.4),
+            (xy(1, 2), 0.0),
+        ];
+        assert_eq!(pixel_perfect(path.to_vec()), [xy(0, 0), xy(0, 1), xy(1, 2)]);
+    }
+
+    #[test]
+    fn trace_works() {
+        let samples = (0..=16).map(|i| {
+            let t = f64::from(i) / 4.0;
+            (t, t * t / 4.0)
+        });
+        let path = trace(samples);
+        assert_eq!(path.first(), Some(&xy(0, 0)));
+        assert_eq!(path.last(), Some(&xy(4, 4)));
+        for w in path.windows(2) {
+            let (dx, dy) = ((w[1].x - w[0].x).abs(), (w[1].y - w[0].y).abs());
+            assert!(dx <= 1 && dy <= 1, "{w:?}");
+        }
+        for w in path.windows(3) {
+            let (dx, dy) = ((w[2].x - w[0].x).abs(), (w[2].y - w[0].y).abs());
+            assert!(dx != 1 || dy != 1, "L-corner: {w:?}");
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    shapes::{polyline, Stroke},
+    Color, Image, Pixel, Position,
+};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    EvenOdd,
+    #[default]
+    NonZero,
+}
+
+#[derive(Debug, Clone)]
+pub struct Polygon {
+    pub color: Color,
+    pub points: Vec<Position>,
+    pub fill: bool,
+    pub fill_rule: FillRule,
+    pub stroke: Stroke,
+}
+
+impl Polygon {
+    pub const fn new() -> Self {
+        Self {
+            color: Color::BLACK,
+            points: Vec::new(),
+            fill: false,
+            fill_rule: FillRule::NonZero,
+            stroke: Stroke::new(),
+        }
+    }
+
+    pub fn color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn point(mut self, point: Position) -> Self {
+        self.points.push(point);
+        self
+    }
+
+    pub fn points(mut self, points: impl IntoIterator<Item = Position>) -> Self {
+        self.points.extend(points);
+        self
+    }
+
+    pub fn fill(self) -> Self {
+        Self { fill: true, ..self }
+    }
+
+    pub fn fill_rule(self, fill_rule: FillRule) -> Self {
+        Self { fill_rule, ..self }
+    }
+
+    pub fn stroke(self, stroke: Stroke) -> Self {
+        Self { stroke, ..self }
+    }
+
+    pub fn to_image(self) -> Image {
+        self.into_iter().collect()
+    }
+
+    fn edges(&self) -> impl '_ + Iterator<Item = (Position, Position)> {
+        self.points
+            .iter()
+            .copied()
+            .zip(self.points.iter().copied().cycle().skip(1))
+    }
+
+    fn inside_positions(&self) -> Vec<Position> {
+        let (Some(min_y), Some(max_y)) = (
+            self.points.iter().map(|p| p.y).min(),
+            self.points.iter().map(|p| p.y).max(),
+        ) else {
+            return Vec::new();
+        };
+
+        let mut positions = Vec::new();
+        for y in min_y..=max_y {
+            // Scanline at the pixel centers: (x-coordinate, winding direction) of each crossing edge.
+            let mut crossings = self
+                .edges()
+                .filter_map(|(a, b)| {
+                    let dir = if a.y <= y && y < b.y {
+                        1
+                    } else if b.y <= y && y < a.y {
+                        -1
+                    } else {
+                        return None;
+                    };
+                    let t = f64::from(y - a.y) / f64::from(b.y - a.y);
+                    Some((f64::from(a.x) + t * f64::from(b.x - a.x), dir))
+                })
+                .collect::<Vec<_>>();
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+            for (i, &(x, dir)) in crossings.iter().enumerate() {
+                winding += dir;
+                let inside = match self.fill_rule {
+                    FillRule::EvenOdd => (i + 1) % 2 == 1,
+                    FillRule::NonZero => winding != 0,
+                };
+                let Some(&(next_x, _)) = crossings.get(i + 1) else {
+                    break;
+                };
+                if inside {
+                    let start = x.ceil() as i16;
+                    let end = next_x.floor() as i16;
+                    positions.extend((start..=end).map(|x| Position::xy(x, y)));
+                }
+            }
+        }
+        positions
+    }
+}
+
+impl Default for Polygon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoIterator for Polygon {
+    type Item = Pixel;
+    type IntoIter = Box<dyn Iterator<Item = Pixel>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut points = self.points.clone();
+        points.extend(self.points.first().copied());
+        let mut path = polyline::segment_path(&points);
+        if path.len() > 1 {
+            // The closing segment ends at the starting point.
+            path.pop();
+        }
+
+        let mut pixels = self.stroke.to_pixels(path, self.color);
+        if self.fill {
+            let outline = pixels.iter().map(|p| p.position).collect::<BTreeSet<_>>();
+            pixels.extend(
+                self.inside_positions()
+                    .into_iter()
+                    .filter(|p| !outline.contains(p))
+                    .map(|p| Pixel::new(p, self.color)),
+            );
+        }
+        Box::new(pixels.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xy;
+
+    #[test]
+    fn fill_rules_work() {
+        let star = Polygon::new()
+            .color(Color::RED)
+            .points([xy(4, 0), xy(7, 8), xy(0, 3), xy(8, 3), xy(1, 8)])
+            .fill();
+
+        let nonzero = star.clone().to_image();
+        let evenodd = star.fill_rule(FillRule::EvenOdd).to_image();
+        assert!(nonzero.pixels.contains_key(&xy(4, 4)));
+        assert!(!evenodd.pixels.contains_key(&xy(4, 4)));
+        assert!(evenodd.pixels.contains_key(&xy(4, 2)));
+    }
+
+    #[test]
+    fn shared_vertices_are_drawn_once() {
+        let color = Color::RED.alpha(128);
+        let triangle = Polygon::new()
+            .color(color)
+            .points([xy(0, 0), xy(4, 0), xy(0, 4)])
+            .to_image();
+        assert!(triangle.pixels.values().all(|c| *c == color));
+        assert_eq!(triangle.pixels.len(), 12);
+    }
+}
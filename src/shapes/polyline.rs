@@ -0,0 +1,78 @@
+use crate::{
+    shapes::{Line, Stroke},
+    Color, Image, Pixel, Position,
+};
+
+#[derive(Debug, Clone)]
+pub struct Polyline {
+    pub color: Color,
+    pub points: Vec<Position>,
+    pub stroke: Stroke,
+}
+
+impl Polyline {
+    pub const fn new() -> Self {
+        Self {
+            color: Color::BLACK,
+            points: Vec::new(),
+            stroke: Stroke::new(),
+        }
+    }
+
+    pub fn color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn point(mut self, point: Position) -> Self {
+        self.points.push(point);
+        self
+    }
+
+    pub fn points(mut self, points: impl IntoIterator<Item = Position>) -> Self {
+        self.points.extend(points);
+        self
+    }
+
+    pub fn stroke(self, stroke: Stroke) -> Self {
+        Self { stroke, ..self }
+    }
+
+    pub fn to_image(self) -> Image {
+        self.into_iter().collect()
+    }
+}
+
+impl Default for Polyline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoIterator for Polyline {
+    type Item = Pixel;
+    type IntoIter = Box<dyn Iterator<Item = Pixel>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let path = segment_path(&self.points);
+        Box::new(self.stroke.to_pixels(path, self.color).into_iter())
+    }
+}
+
+/// Returns the positions of the lines connecting consecutive `points`, in order.
+///
+/// Joints shared by adjacent segments appear only once, so that
+/// semi-transparent colors are not blended twice there.
+pub(crate) fn segment_path(points: &[Position]) -> Vec<Position> {
+    let mut path = points.iter().copied().take(1).collect::<Vec<_>>();
+    for segment in points.windows(2) {
+        let line = Line::new()
+            .position(segment[0])
+            .vector(segment[1] - segment[0]);
+        for p in line.into_iter().map(|p| p.position) {
+            if path.last() != Some(&p) {
+                path.push(p);
+            }
+        }
+    }
+    path
+}
@@ -1,4 +1,8 @@
-use crate::{Color, Image, Pixel, Position, Size};
+use crate::{
+    shapes::{ellipse, path, Stroke},
+    Color, Image, Pixel, Position, Size,
+};
+use std::collections::BTreeSet;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Rectangle {
@@ -6,6 +10,8 @@ pub struct Rectangle {
     pub position: Position,
     pub size: Size,
     pub fill: bool,
+    pub stroke: Stroke,
+    pub radius: u16,
 }
 
 impl Rectangle {
@@ -15,6 +21,8 @@ impl Rectangle {
             position: Position::ORIGIN,
             size: Size::EMPTY,
             fill: false,
+            stroke: Stroke::new(),
+            radius: 0,
         }
     }
 
@@ -34,6 +42,16 @@ impl Rectangle {
         Self { fill: true, ..self }
     }
 
+    /// Sets the stroke of the outline, which grows inward as its width increases.
+    pub const fn stroke(self, stroke: Stroke) -> Self {
+        Self { stroke, ..self }
+    }
+
+    /// Sets the radius of the rounded corners.
+    pub const fn radius(self, radius: u16) -> Self {
+        Self { radius, ..self }
+    }
+
     pub fn to_image(self) -> Image {
         self.into_iter().collect()
     }
@@ -54,22 +72,117 @@ impl IntoIterator for Rectangle {
             return Box::new(
                 self.size
                     .positions()
+                    .filter(move |&p| is_inside(self.size, self.radius, p))
                     .map(move |p| Pixel::new(self.position + p, self.color)),
             );
         }
 
-        let pixel = Pixel::new(self.position, self.color);
-        let mut pixels = Vec::new();
-        for x in 0..self.size.width {
-            pixels.push(pixel.map_position(|p| p.move_x_unsigned(x)));
-            pixels.push(pixel.map_position(|p| p.move_xy_unsigned(x, self.size.height - 1)));
-        }
-        for y in 0..self.size.height {
-            pixels.push(pixel.map_position(|p| p.move_y_unsigned(y)));
-            pixels.push(pixel.map_position(|p| p.move_xy_unsigned(self.size.width - 1, y)));
+        // The path is inset so that the brush of the stroke stays inside the rectangle.
+        let inset = self.stroke.width.saturating_sub(1);
+        let brush_offset = self.stroke.brush_offset();
+        let start = Position::xy(brush_offset as i16, brush_offset as i16);
+        let size = self.size.map_wh(|w, h| {
+            (
+                w.saturating_sub(inset).max(1),
+                h.saturating_sub(inset).max(1),
+            )
+        });
+        let path = if self.radius == 0 {
+            perimeter(size).collect::<Vec<_>>()
+        } else {
+            rounded_perimeter(size, self.radius.saturating_sub(brush_offset))
+        };
+        let path = path.into_iter().map(move |p| self.position + start + p);
+        Box::new(
+            self.stroke
+                .to_pixels(path, self.color)
+                .into_iter()
+                .filter(move |p| is_inside(self.size, self.radius, p.position - self.position)),
+        )
+    }
+}
+
+fn is_inside(size: Size, radius: u16, p: Position) -> bool {
+    if !size.contains(p) {
+        return false;
+    }
+
+    // Each corner is a quarter of a circle with the diameter `2 * r + 1` (see `Ellipse`).
+    let r = i32::from(radius.min(size.width / 2).min(size.height / 2));
+    let d = 2 * r + 1;
+    let corner_offset = |v: i16, len: u16| {
+        let (v, len) = (i32::from(v), i32::from(len));
+        if v < r {
+            Some(v)
+        } else if v >= len - r {
+            Some(v - (len - r) + r + 1)
+        } else {
+            None
         }
-        pixels.sort();
-        pixels.dedup();
-        Box::new(pixels.into_iter())
+    };
+    let (Some(x), Some(y)) = (
+        corner_offset(p.x, size.width),
+        corner_offset(p.y, size.height),
+    ) else {
+        return true;
+    };
+
+    let dx = 2 * x + 1 - d;
+    let dy = 2 * y + 1 - d;
+    dx * dx + dy * dy <= d * d - 2
+}
+
+fn rounded_perimeter(size: Size, radius: u16) -> Vec<Position> {
+    let inside = size
+        .positions()
+        .filter(|&p| is_inside(size, radius, p))
+        .collect::<BTreeSet<_>>();
+    let mut outline = inside
+        .iter()
+        .copied()
+        .filter(|&p| ellipse::neighbors4(p).any(|n| !inside.contains(&n)))
+        .collect();
+    ellipse::remove_l_corners(&mut outline);
+    path::order_loop(outline)
+}
+
+/// Returns the edge positions of `size` in clockwise order, starting from the top-left corner.
+fn perimeter(size: Size) -> impl Iterator<Item = Position> {
+    let w = size.width as i16;
+    let h = size.height as i16;
+    let top = (0..w).map(|x| Position::xy(x, 0));
+    let right = (1..h).map(move |y| Position::xy(w - 1, y));
+    let bottom = (0..w - 1)
+        .rev()
+        .map(move |x| Position::xy(x, h - 1))
+        .take_while(move |_| h > 1);
+    let left = (1..h - 1)
+        .rev()
+        .map(|y| Position::xy(0, y))
+        .take_while(move |_| w > 1);
+    top.chain(right).chain(bottom).chain(left)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounded_corners_work() {
+        let rect = Rectangle::new()
+            .color(Color::RED)
+            .size(Size::new(8, 7))
+            .radius(3);
+        let outline = Image::from_text(
+            [('#', Color::RED)],
+            "  ####  \n #    # \n#      #\n#      #\n#      #\n #    # \n  ####  \n",
+        );
+        assert_eq!(rect.to_image().to_text(), outline.to_text());
+
+        let filled = Image::from_text(
+            [('#', Color::RED)],
+            "  ####  \n ###### \n########\n########\n########\n ###### \n  ####  \n",
+        );
+        assert_eq!(rect.fill().to_image().to_text(), filled.to_text());
     }
 }
@@ -0,0 +1,155 @@
+use crate::{Color, Pixel, Position, Size};
+use std::collections::BTreeSet;
+
+/// How the outline of a shape is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stroke {
+    /// Side length of the square brush stamped on each pixel of the path.
+    pub width: u16,
+
+    /// Alternating lengths (in pixels along the path) of drawn and skipped parts.
+    ///
+    /// An empty pattern means a solid stroke.
+    pub dashes: &'static [u16],
+
+    /// Colors cycled through along the path.
+    ///
+    /// If empty, the color of the shape is used.
+    pub colors: &'static [Color],
+}
+
+impl Stroke {
+    pub const fn new() -> Self {
+        Self {
+            width: 1,
+            dashes: &[],
+            colors: &[],
+        }
+    }
+
+    pub const fn width(self, width: u16) -> Self {
+        Self { width, ..self }
+    }
+
+    pub const fn dashes(self, dashes: &'static [u16]) -> Self {
+        Self { dashes, ..self }
+    }
+
+    pub const fn colors(self, colors: &'static [Color]) -> Self {
+        Self { colors, ..self }
+    }
+
+    /// Returns how far the brush extends before the path position (in both axes).
+    pub(crate) const fn brush_offset(self) -> u16 {
+        self.width.saturating_sub(1) / 2
+    }
+
+    pub(crate) fn to_pixels(
+        self,
+        path: impl IntoIterator<Item = Position>,
+        color: Color,
+    ) -> Vec<Pixel> {
+        let brush_offset = -(self.brush_offset() as i16);
+        let brush_start = Position::xy(brush_offset, brush_offset);
+        let dash_cycle = self.dashes.iter().map(|&n| u64::from(n)).sum::<u64>();
+
+        let mut visited = BTreeSet::new();
+        let mut pixels = Vec::new();
+        for (i, position) in path.into_iter().enumerate() {
+            if dash_cycle > 0 && !self.is_dash_on(i as u64 % dash_cycle) {
+                continue;
+            }
+            let color = if self.colors.is_empty() {
+                color
+            } else {
+                self.colors[i % self.colors.len()]
+            };
+            for offset in Size::square(self.width).positions() {
+                let position = position + brush_start + offset;
+                // Overlapping brush stamps are drawn only once so that alpha blending is not doubled.
+                if visited.insert(position) {
+                    pixels.push(Pixel::new(position, color));
+                }
+            }
+        }
+        pixels
+    }
+
+    fn is_dash_on(self, mut n: u64) -> bool {
+        for (i, &len) in self.dashes.iter().enumerate() {
+            if n < u64::from(len) {
+                return i % 2 == 0;
+            }
+            n -= u64::from(len);
+        }
+        false
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shapes::{Line, Rectangle},
+        xy, Image,
+    };
+
+    fn render(image: &Image, size: Size) -> String {
+        let mut text = String::new();
+        for p in size.positions() {
+            text.push(if image.pixels.contains_key(&p) {
+                '#'
+            } else {
+                '.'
+            });
+            if p.x + 1 == size.width as i16 {
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    #[test]
+    fn rectangle_stroke_works() {
+        let size = Size::new(5, 4);
+        let rect = Rectangle::new().size(size);
+        assert_eq!(
+            render(&rect.to_image(), size),
+            "#####\n#...#\n#...#\n#####\n"
+        );
+
+        let thick = rect.stroke(Stroke::new().width(2)).to_image();
+        assert_eq!(render(&thick, size), "#####\n#####\n#####\n#####\n");
+
+        let thick = rect.size(Size::new(6, 5)).stroke(Stroke::new().width(2));
+        assert_eq!(
+            render(&thick.to_image(), Size::new(6, 5)),
+            "######\n######\n##..##\n######\n######\n"
+        );
+
+        let dotted = rect.stroke(Stroke::new().dashes(&[1, 1])).to_image();
+        assert_eq!(render(&dotted, size), "#.#.#\n.....\n#...#\n.#.#.\n");
+    }
+
+    #[test]
+    fn line_stroke_works() {
+        let line = Line::new()
+            .vector(xy(3, 0))
+            .stroke(Stroke::new().colors(&[Color::RED, Color::BLUE]));
+        let colors = line.into_iter().map(|p| p.color).collect::<Vec<_>>();
+        assert_eq!(colors, [Color::RED, Color::BLUE, Color::RED, Color::BLUE]);
+
+        let thick = Line::new()
+            .position(xy(1, 1))
+            .vector(xy(2, 0))
+            .stroke(Stroke::new().width(3))
+            .to_image();
+        assert_eq!(render(&thick, Size::new(5, 3)), "#####\n#####\n#####\n");
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{font::BitmapFont, Color, Image, Pixel, Position};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct Text<'a> {
+    pub font: &'a BitmapFont,
+    pub text: String,
+    pub color: Option<Color>,
+    pub position: Position,
+    pub align: TextAlign,
+    pub max_width: Option<u16>,
+}
+
+impl<'a> Text<'a> {
+    pub fn new(font: &'a BitmapFont, text: &str) -> Self {
+        Self {
+            font,
+            text: text.to_owned(),
+            color: None,
+            position: Position::ORIGIN,
+            align: TextAlign::Left,
+            max_width: None,
+        }
+    }
+
+    /// Draws all glyph pixels in `color` instead of the colors of the glyph images.
+    pub fn color(self, color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+
+    pub fn position(self, position: Position) -> Self {
+        Self { position, ..self }
+    }
+
+    /// Aligns lines within the widest line, or within `max_width` if it is set.
+    pub fn align(self, align: TextAlign) -> Self {
+        Self { align, ..self }
+    }
+
+    /// Wraps lines at spaces so that they fit in `max_width` pixels.
+    ///
+    /// Words wider than `max_width` are placed on their own lines without being broken.
+    pub fn max_width(self, max_width: u16) -> Self {
+        Self {
+            max_width: Some(max_width),
+            ..self
+        }
+    }
+
+    pub fn to_image(self) -> Image {
+        self.into_iter().collect()
+    }
+
+    /// Returns the lines of the text after wrapping.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in self.text.lines() {
+            let Some(max_width) = self.max_width else {
+                lines.push(paragraph.to_owned());
+                continue;
+            };
+
+            let mut line = String::new();
+            for word in paragraph.split(' ') {
+                if line.is_empty() {
+                    line.push_str(word);
+                    continue;
+                }
+                let candidate = format!("{line} {word}");
+                if self.font.get_text_width(&candidate) <= max_width {
+                    line = candidate;
+                } else {
+                    lines.push(std::mem::replace(&mut line, word.to_owned()));
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+impl IntoIterator for Text<'_> {
+    type Item = Pixel;
+    type IntoIter = Box<dyn Iterator<Item = Pixel>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let lines = self.lines();
+        let widths = lines
+            .iter()
+            .map(|line| self.font.get_text_width(line))
+            .collect::<Vec<_>>();
+        let block_width = self
+            .max_width
+            .unwrap_or_else(|| widths.iter().copied().max().unwrap_or(0));
+
+        let mut pixels = Vec::new();
+        for (i, (line, width)) in lines.iter().zip(widths).enumerate() {
+            let mut x = match self.align {
+                TextAlign::Left => 0,
+                TextAlign::Center => block_width.saturating_sub(width) as i16 / 2,
+                TextAlign::Right => block_width.saturating_sub(width) as i16,
+            };
+            let y = i as i16 * self.font.line_height as i16;
+
+            let mut chars = line.chars().peekable();
+            while let Some(ch) = chars.next() {
+                if let Some(glyph) = self.font.get_glyph(ch) {
+                    let offset = self.position + Position::xy(x, y);
+                    pixels.extend(glyph.image.iter().map(|pixel| {
+                        Pixel::new(pixel.position + offset, self.color.unwrap_or(pixel.color))
+                    }));
+                }
+                x += self.font.get_advance(ch, chars.peek().copied());
+            }
+        }
+        Box::new(pixels.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_works() {
+        let font = BitmapFont::tiny();
+
+        let text = Text::new(&font, "ab cd efg").max_width(8);
+        assert_eq!(text.lines(), ["ab", "cd", "efg"]);
+
+        let image = text.align(TextAlign::Right).color(Color::RED).to_image();
+        assert_eq!(image.get_color(Position::xy(1, 0)), Color::TRANSPARENT);
+        assert_eq!(image.get_color(Position::xy(2, 0)), Color::RED);
+        assert_eq!(image.get_color(Position::xy(0, 12)), Color::RED);
+
+        let image = Text::new(&font, "a")
+            .max_width(8)
+            .align(TextAlign::Right)
+            .to_image();
+        assert_eq!(image.get_region().right(), 7);
+
+        let kerned = font.clone().kerning(('a', 'b'), -1);
+        assert_eq!(kerned.get_text_width("ab"), 6);
+        assert_eq!(kerned.get_text_width("ba"), 7);
+    }
+}
@@ -0,0 +1,404 @@
+use crate::{Animation, Image, Pixel, Position, Region, Size};
+use std::{fmt::Write, num::NonZeroU16, time::Duration};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Places sprites in equally sized cells.
+    ///
+    /// If `columns` is `None`, the number of columns is chosen to make the sheet roughly square.
+    Grid { columns: Option<NonZeroU16> },
+
+    /// Places sprites on shelves sorted by height, wasting as little space as possible.
+    #[default]
+    Packed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpriteSheetFrame {
+    pub name: String,
+    pub region: Region,
+    /// Position of the top-left corner of the sprite before it was moved into the sheet.
+    pub source_offset: Position,
+    pub duration: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpriteSheet {
+    pub image: Image,
+    pub frames: Vec<SpriteSheetFrame>,
+}
+
+impl SpriteSheet {
+    /// Returns the frame table in the JSON format used by TexturePacker and Aseprite ("Array" variant).
+    ///
+    /// `image_path` is the path of the file to which `self.image` is written.
+    pub fn to_json(&self, image_path: &str) -> String {
+        let mut json = String::new();
+        let size = self.get_size();
+        let _ = writeln!(json, "{{\n  \"frames\": [");
+        for (i, frame) in self.frames.iter().enumerate() {
+            let Region { start, size } = frame.region;
+            let (w, h) = (size.width, size.height);
+            // The untrimmed source starts at the origin, or further up and left to keep
+            // `spriteSourceSize` non-negative.
+            let source_start = frame.source_offset.min(Position::ORIGIN);
+            let offset = frame.source_offset - source_start;
+            let (source_w, source_h) = (offset.x as u16 + w, offset.y as u16 + h);
+            let trimmed = (source_w, source_h) != (w, h);
+            let _ = write!(
+                json,
+                "    {{ \"filename\": {}, \"frame\": {{ \"x\": {}, \"y\": {}, \"w\": {w}, \"h\": {h} }}, \
+                 \"rotated\": false, \"trimmed\": {trimmed}, \
+                 \"spriteSourceSize\": {{ \"x\": {}, \"y\": {}, \"w\": {w}, \"h\": {h} }}, \
+                 \"sourceSize\": {{ \"w\": {source_w}, \"h\": {source_h} }}",
+                json_string(&frame.name),
+                start.x,
+                start.y,
+                offset.x,
+                offset.y,
+            );
+            if let Some(duration) = frame.duration {
+                let _ = write!(json, ", \"duration\": {}", duration.as_millis());
+            }
+            let comma = if i + 1 < self.frames.len() { "," } else { "" };
+            let _ = writeln!(json, " }}{comma}");
+        }
+        let _ = writeln!(json, "  ],");
+        let _ = writeln!(
+            json,
+            "  \"meta\": {{ \"app\": \"{}\", \"version\": \"{}\", \"image\": {}, \
+             \"format\": \"RGBA8888\", \"size\": {{ \"w\": {}, \"h\": {} }}, \"scale\": \"1\" }}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            json_string(image_path),
+            size.width,
+            size.height,
+        );
+        json.push_str("}\n");
+        json
+    }
+
+    fn get_size(&self) -> Size {
+        let max = self
+            .frames
+            .iter()
+            .filter(|f| !f.region.is_empty())
+            .map(|f| f.region.bottom_right())
+            .fold(Position::xy(-1, -1), Position::max);
+        Size::new((max.x + 1) as u16, (max.y + 1) as u16)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SpriteSheetPacker {
+    sprites: Vec<(String, Image, Option<Duration>)>,
+    layout: Layout,
+    spacing: u16,
+}
+
+impl SpriteSheetPacker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sprite(mut self, name: &str, image: Image) -> Self {
+        self.sprites.push((name.to_owned(), image, None));
+        self
+    }
+
+    /// Adds all frames of `anime`, named by their indices.
+    pub fn animation(mut self, anime: &Animation) -> Self {
+        for (i, frame) in anime.frames.iter().enumerate() {
+            let duration = anime.get_nth_frame_duration(i);
+            self.sprites
+                .push((i.to_string(), frame.clone(), Some(duration)));
+        }
+        self
+    }
+
+    pub fn layout(self, layout: Layout) -> Self {
+        Self { layout, ..self }
+    }
+
+    /// Sets the number of transparent pixels between adjacent sprites.
+    pub fn spacing(self, spacing: u16) -> Self {
+        Self { spacing, ..self }
+    }
+
+    pub fn pack(self) -> SpriteSheet {
+        // Sprites are trimmed to their bounding boxes; the offsets are kept in the frames.
+        let regions = self
+            .sprites
+            .iter()
+            .map(|(_, image, _)| image.get_region())
+            .collect::<Vec<_>>();
+        let sizes = regions.iter().map(|r| r.size).collect::<Vec<_>>();
+
+        let starts = match self.layout {
+            Layout::Grid { columns } => self.grid_layout(&sizes, columns),
+            Layout::Packed => self.packed_layout(&sizes),
+        };
+
+        let mut sheet = SpriteSheet {
+            image: Image::new(),
+            frames: Vec::new(),
+        };
+        for (((name, image, duration), source), start) in
+            self.sprites.into_iter().zip(regions).zip(starts)
+        {
+            let mut image = image.offset(start - source.start);
+            image.name = Some(name.clone());
+            sheet.image.children.push(image);
+            sheet.frames.push(SpriteSheetFrame {
+                name,
+                region: Region::new(start, source.size),
+                source_offset: source.start,
+                duration,
+            });
+        }
+        sheet
+    }
+
+    fn grid_layout(&self, sizes: &[Size], columns: Option<NonZeroU16>) -> Vec<Position> {
+        let cell = sizes.iter().copied().fold(Size::EMPTY, Size::max);
+        let columns = columns
+            .map(|c| usize::from(c.get()))
+            .unwrap_or_else(|| (sizes.len() as f64).sqrt().ceil().max(1.0) as usize);
+        (0..sizes.len())
+            .map(|i| {
+                let x = (i % columns) as u16 * (cell.width + self.spacing);
+                let y = (i / columns) as u16 * (cell.height + self.spacing);
+                Position::xy(x as i16, y as i16)
+            })
+            .collect()
+    }
+
+    fn packed_layout(&self, sizes: &[Size]) -> Vec<Position> {
+        let total_area = sizes
+            .iter()
+            .map(|s| (u32::from(s.width) + u32::from(self.spacing)) * u32::from(s.height))
+            .sum::<u32>();
+        let max_width = sizes.iter().map(|s| s.width).max().unwrap_or(0);
+        let sheet_width = max_width.max(f64::from(total_area).sqrt().ceil() as u16);
+
+        let mut order = (0..sizes.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| std::cmp::Reverse(sizes[i].height));
+
+        let mut starts = vec![Position::ORIGIN; sizes.len()];
+        let (mut x, mut y, mut shelf_height) = (0u16, 0u16, 0u16);
+        for i in order {
+            let size = sizes[i];
+            if x > 0 && x + size.width > sheet_width {
+                x = 0;
+                y += shelf_height + self.spacing;
+                shelf_height = 0;
+            }
+            starts[i] = Position::xy(x as i16, y as i16);
+            x += size.width + self.spacing;
+            shelf_height = shelf_height.max(size.height);
+        }
+        starts
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SpriteSheetSlicer {
+    cell_size: Size,
+    margin: u16,
+    spacing: u16,
+    skip_empty: bool,
+}
+
+impl SpriteSheetSlicer {
+    pub const fn new(cell_size: Size) -> Self {
+        Self {
+            cell_size,
+            margin: 0,
+            spacing: 0,
+            skip_empty: false,
+        }
+    }
+
+    /// Sets the number of pixels between the sheet edges and the outermost cells.
+    pub const fn margin(self, margin: u16) -> Self {
+        Self { margin, ..self }
+    }
+
+    /// Sets the number of pixels between adjacent cells.
+    pub const fn spacing(self, spacing: u16) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Skips cells that have no visible pixels.
+    pub const fn skip_empty(self) -> Self {
+        Self {
+            skip_empty: true,
+            ..self
+        }
+    }
+
+    /// Returns the cells of `sheet` in row-major order, each moved to the origin.
+    ///
+    /// Cells only partially covered by the bounding box of `sheet` are included, since trailing
+    /// transparent pixels are usually not stored.
+    pub fn slice(&self, sheet: &Image) -> Vec<Image> {
+        if self.cell_size.is_empty() {
+            return Vec::new();
+        }
+
+        let end = sheet.get_region().bottom_right();
+        let starts = |end: i16, cell: u16| {
+            let step = cell + self.spacing;
+            (0..)
+                .map(move |i| i32::from(self.margin) + i * i32::from(step))
+                .take_while(move |&v| v <= i32::from(end))
+                .map(|v| v as i16)
+        };
+
+        let mut cells = Vec::new();
+        for y in starts(end.y, self.cell_size.height) {
+            for x in starts(end.x, self.cell_size.width) {
+                let start = Position::xy(x, y);
+                let cell = self
+                    .cell_size
+                    .positions()
+                    .map(|p| Pixel::new(p, sheet.get_color(p + start)))
+                    .filter(|p| !p.color.is_transparent())
+                    .collect::<Image>();
+                if !(self.skip_empty && cell.pixels.is_empty()) {
+                    cells.push(cell);
+                }
+            }
+        }
+        cells
+    }
+
+    pub fn to_animation(&self, sheet: &Image) -> Animation {
+        Animation::new().frames(self.slice(sheet))
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::Rectangle, Color};
+
+    #[test]
+    fn pack_works() {
+        let sprite = |w, h| {
+            Rectangle::new()
+                .size(Size::new(w, h))
+                .color(Color::RED)
+                .fill()
+                .to_image()
+        };
+        let packer = SpriteSheetPacker::new()
+            .sprite("a", sprite(4, 2))
+            .sprite("b", sprite(2, 3))
+            .sprite("c\"", sprite(3, 3));
+
+        let sheet = packer.clone().layout(Layout::Grid { columns: None }).pack();
+        let regions = sheet.frames.iter().map(|f| f.region).collect::<Vec<_>>();
+        assert_eq!(
+            regions,
+            [
+                Region::new(Position::xy(0, 0), Size::new(4, 2)),
+                Region::new(Position::xy(4, 0), Size::new(2, 3)),
+                Region::new(Position::xy(0, 3), Size::new(3, 3)),
+            ]
+        );
+
+        let sheet = packer.spacing(1).pack();
+        for (i, a) in sheet.frames.iter().enumerate() {
+            for b in &sheet.frames[i + 1..] {
+                assert!(!a
+                    .region
+                    .positions()
+                    .any(|p| b.region.positions().any(|q| p == q)));
+            }
+            assert!(a
+                .region
+                .positions()
+                .all(|p| sheet.image.get_color(p) == Color::RED));
+        }
+        assert_eq!(sheet.image.iter().count(), 8 + 6 + 9);
+
+        let json = sheet.to_json("sheet.png");
+        assert!(json.contains(r#""filename": "c\"""#));
+        assert!(json.contains(r#""image": "sheet.png""#));
+    }
+
+    #[test]
+    fn pack_offset_sprites_works() {
+        let dot = |x, y| Image::from_iter([Pixel::new(Position::xy(x, y), Color::RED)]);
+        let sheet = SpriteSheetPacker::new()
+            .sprite("a", dot(5, 5))
+            .sprite("b", dot(-3, -2))
+            .layout(Layout::Grid { columns: None })
+            .pack();
+        assert_eq!(
+            sheet.frames.iter().map(|f| f.region).collect::<Vec<_>>(),
+            [
+                Region::new(Position::xy(0, 0), Size::square(1)),
+                Region::new(Position::xy(1, 0), Size::square(1)),
+            ]
+        );
+        assert_eq!(sheet.frames[1].source_offset, Position::xy(-3, -2));
+        assert_eq!(
+            sheet.image.get_region(),
+            Region::new(Position::ORIGIN, Size::new(2, 1))
+        );
+
+        let json = sheet.to_json("sheet.png");
+        assert!(json.contains(r#""trimmed": true, "spriteSourceSize": { "x": 5, "y": 5, "w": 1, "h": 1 }, "sourceSize": { "w": 6, "h": 6 }"#));
+        assert!(json.contains(r#""trimmed": false, "spriteSourceSize": { "x": 0, "y": 0, "w": 1, "h": 1 }, "sourceSize": { "w": 1, "h": 1 }"#));
+    }
+
+    #[test]
+    fn slice_works() {
+        let sheet = Image::from_text(
+            [('#', Color::BLACK)],
+            concat!("      \n", " ## # \n", " #  # \n", "      \n", " #    \n", "      \n",),
+        );
+        let slicer = SpriteSheetSlicer::new(Size::square(2)).margin(1).spacing(1);
+        let cells = slicer.slice(&sheet);
+        assert_eq!(
+            cells.iter().map(|c| c.pixels.len()).collect::<Vec<_>>(),
+            [3, 2, 1, 0]
+        );
+        assert!(cells[1].pixels.contains_key(&Position::xy(0, 1)));
+
+        let anime = slicer.skip_empty().to_animation(&sheet);
+        assert_eq!(anime.frames.len(), 3);
+
+        // The bottom sprites do not reach the last line of their cells.
+        let sparse = Size::new(8, 6)
+            .positions()
+            .map(|p| Pixel::new(p, Color::BLACK))
+            .collect::<Image>();
+        let cells = SpriteSheetSlicer::new(Size::square(4)).slice(&sparse);
+        assert_eq!(
+            cells.iter().map(|c| c.pixels.len()).collect::<Vec<_>>(),
+            [16, 16, 8, 8]
+        );
+    }
+}